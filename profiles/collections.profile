@@ -0,0 +1,5 @@
+# Data structures carrying large collections of nested items
+builder-function = 0
+collection-field = 5
+builder-field = 5
+into-field = 2
@@ -0,0 +1,14 @@
+# Feature weights used to rank contenders in documentation.
+#
+# One `<feature> = <weight>` line per feature, unlisted features weight 1.
+# Pass your own profile to the report binary: `just report --profile <path>`.
+builder-function = 1
+into-field = 1
+option-field = 1
+default-struct = 1
+collection-field = 1
+builder-field = 1
+into-builder = 1
+chain-call = 1
+builder-customization = 1
+builder-reuse = 1
@@ -0,0 +1,115 @@
+//! Allocation counting, through a [`GlobalAlloc`] wrapper of the system allocator.
+//!
+//! Counts are kept per thread, so that concurrent threads don't blur measures. The `bench` binary installs it:
+//!
+//! ```rust
+//! use rust_benchmark_setter::bench::alloc::{self,Counting};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: Counting = Counting;
+//!
+//! # fn main() {
+//! let (counts, _) = alloc::count(|| vec![1u8; 16]);
+//! assert!(alloc::is_installed());
+//! assert_eq!(1, counts.allocations);
+//! assert_eq!(16, counts.bytes);
+//! # }
+//! ```
+//!
+//! Reallocations count as a new allocation of the new size.
+
+use std::alloc::{GlobalAlloc,Layout,System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool,Ordering};
+
+/// Counting wrapper of [`System`] allocator
+pub struct Counting;
+
+/// Allocations performed by current thread
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub struct Counts {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocations: 0, bytes: 0 }) };
+}
+
+fn record(bytes: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    let _ = COUNTS.try_with(|counts| {
+        let mut current = counts.get();
+        current.allocations += 1;
+        current.bytes += bytes as u64;
+        counts.set(current);
+    });
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Whether [`Counting`] is the global allocator
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Allocations performed so far by current thread
+pub fn counts() -> Counts {
+    COUNTS.with(Cell::get)
+}
+
+/// Allocations performed by `run`, along its result
+pub fn count<T, F: FnOnce() -> T>(run: F) -> (Counts, T) {
+    let before = counts();
+    let result = run();
+    let after = counts();
+    (Counts {
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+    }, result)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    pub fn counts_allocations() {
+        let (counts, _) = count(|| (Box::new(1u64), String::with_capacity(10)));
+
+        assert!(is_installed());
+        assert_eq!(Counts { allocations: 2, bytes: 18 }, counts);
+    }
+
+    #[test]
+    pub fn counts_reallocations() {
+        let mut list = Vec::<u8>::with_capacity(4);
+        let (counts, _) = count(|| list.reserve_exact(8));
+
+        assert_eq!(Counts { allocations: 1, bytes: 8 }, counts);
+    }
+}
@@ -0,0 +1,356 @@
+//! Persisted benchmark baselines, and regression gating against them.
+//!
+//! A baseline records median timings keyed by contender, contender version and scenario, one per line:
+//!
+//! ```text
+//! timing derive-builder 0.12.0 usage 435.9 430.6..443.5
+//! ```
+//!
+//! Trailing field is the confidence interval of the median, `-` when unknown. Saving into an existing baseline
+//! replaces timings of same key, keeping other versions. `just bench --save <path>` records one,
+//! then `just bench --compare <path> --threshold 5` exits with failure when a contender got slower by more than 5%.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::bench::baseline::Baseline;
+//! let before = Baseline::parse("timing derive-builder 0.12.0 usage 400 390..410").unwrap();
+//! let after = Baseline::parse("timing derive-builder 0.13.0 usage 480 470..490").unwrap();
+//!
+//! let deltas = before.compare(&after, 0.1);
+//! assert_eq!(1, deltas.len());
+//! assert!(deltas[0].regressed);
+//! assert_eq!("derive-builder usage (0.12.0 → 0.13.0): 400.0 → 480.0 ns/op (+20.0%) regression", deltas[0].to_string());
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self,Write};
+use super::Timing;
+use crate::registry::Registry;
+use crate::report::{self,Format};
+
+/// Baseline key
+#[derive(Clone,Debug,Eq,Ord,PartialEq,PartialOrd)]
+pub struct Key {
+    pub contender: String,
+    pub version: String,
+    pub scenario: String,
+}
+
+/// Recorded median timing
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Record {
+    /// Median nanoseconds per iteration
+    pub nanos: f64,
+    /// Confidence interval of the median, when sampled
+    pub interval: Option<(f64, f64)>,
+}
+
+/// Recorded timings
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Baseline {
+    pub records: BTreeMap<Key, Record>,
+}
+
+/// Invalid baseline line
+#[derive(Clone,Debug,PartialEq)]
+pub struct BaselineError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+/// Timing moved between two baselines
+#[derive(Clone,Debug,PartialEq)]
+pub struct Delta {
+    pub contender: String,
+    pub scenario: String,
+    pub before_version: String,
+    pub after_version: String,
+    pub before: f64,
+    pub after: f64,
+    /// Relative change of median, e.g. `0.2` when 20% slower
+    pub change: f64,
+    /// Whether confidence intervals are disjoint, when both are known
+    pub significant: Option<bool>,
+    /// Slower beyond threshold, and not within noise
+    pub regressed: bool,
+}
+
+/// Numeric components of `version`, to find the latest one
+fn version_order(version: &str) -> Vec<u64> {
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+impl Baseline {
+    /// Record `timings`, taking contender versions from `registry`
+    ///
+    /// Timings of unregistered contenders are skipped.
+    pub fn record(registry: &Registry, timings: &[Timing]) -> Self {
+        let records = timings
+            .iter()
+            .filter_map(|timing| {
+                let contender = registry.get(timing.contender)?;
+                Some((
+                    Key {
+                        contender: timing.contender.to_owned(),
+                        version: contender.declaration().version.to_owned(),
+                        scenario: timing.scenario.to_owned(),
+                    },
+                    Record { nanos: timing.nanos, interval: timing.summary.as_ref().map(|summary| summary.interval) },
+                ))
+            })
+            .collect();
+        Self { records }
+    }
+
+    /// Add `other` records, replacing those of same key
+    pub fn merge(&mut self, other: Baseline) -> &mut Self {
+        self.records.extend(other.records);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for (key, record) in &self.records {
+            let interval = record.interval.map(|(low, high)| format!("{}..{}", low, high)).unwrap_or_else(|| "-".to_owned());
+            writeln!(text, "timing {} {} {} {} {}", key.contender, key.version, key.scenario, record.nanos, interval).unwrap();
+        }
+        text
+    }
+
+    pub fn parse(source: &str) -> Result<Self, BaselineError> {
+        let mut records = BTreeMap::new();
+        for (index, line) in source.lines().enumerate() {
+            let error = |message: String| BaselineError { line: index + 1, message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["timing", contender, version, scenario, nanos, interval @ ..] if interval.len() <= 1 => {
+                    let number = |value: &str| value.parse::<f64>().ok().filter(|value| value.is_finite());
+                    let nanos = number(nanos).ok_or_else(|| error(format!("invalid ns/op '{}'", nanos)))?;
+                    let interval = match interval {
+                        [] | ["-"] => None,
+                        [interval, ..] => Some(interval
+                            .split_once("..")
+                            .and_then(|(low, high)| Some((number(low)?, number(high)?)))
+                            .ok_or_else(|| error(format!("invalid interval '{}'", interval)))?),
+                    };
+                    let key = Key { contender: contender.to_string(), version: version.to_string(), scenario: scenario.to_string() };
+                    records.insert(key, Record { nanos, interval });
+                },
+                _ => return Err(error(format!("unexpected line '{}'", line))),
+            }
+        }
+        Ok(Self { records })
+    }
+
+    /// Record of `contender` and `scenario`, at `version` when recorded, at latest recorded version otherwise
+    pub fn find(&self, contender: &str, scenario: &str, version: &str) -> Option<(&Key, &Record)> {
+        self.records
+            .iter()
+            .filter(|(key, _)| key.contender == contender && key.scenario == scenario)
+            .max_by_key(|(key, _)| (key.version == version, version_order(&key.version)))
+    }
+
+    /// Compare `current` records with matching ones, `threshold` being the tolerated relative slowdown
+    ///
+    /// A slowdown beyond `threshold` isn't a regression when confidence intervals overlap.
+    pub fn compare(&self, current: &Baseline, threshold: f64) -> Vec<Delta> {
+        current.records
+            .iter()
+            .filter_map(|(key, after)| {
+                let (before_key, before) = self.find(&key.contender, &key.scenario, &key.version)?;
+                let change = after.nanos / before.nanos - 1.0;
+                let significant = before.interval.zip(after.interval).map(|((before_low, before_high), (after_low, after_high))| {
+                    before_high < after_low || after_high < before_low
+                });
+                Some(Delta {
+                    contender: key.contender.clone(),
+                    scenario: key.scenario.clone(),
+                    before_version: before_key.version.clone(),
+                    after_version: key.version.clone(),
+                    before: before.nanos,
+                    after: after.nanos,
+                    change,
+                    significant,
+                    regressed: change > threshold && significant != Some(false),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Render `deltas` in given `format`
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::baseline::{self,Baseline};
+/// # use rust_benchmark_setter::report::Format;
+/// let before = Baseline::parse("timing foo 1.0.0 usage 100 -").unwrap();
+/// let after = Baseline::parse("timing foo 1.0.0 usage 95 -").unwrap();
+///
+/// assert_eq!(
+///     "contender,scenario,before_version,after_version,before_ns_per_op,after_ns_per_op,change,significant,regressed\n\
+///      foo,usage,1.0.0,1.0.0,100,95,-0.050,,false\n",
+///     baseline::render(&before.compare(&after, 0.05), Format::Csv),
+/// );
+/// ```
+pub fn render(deltas: &[Delta], format: Format) -> String {
+    let significant = |delta: &Delta| match delta.significant {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "-",
+    };
+    let status = |delta: &Delta| if delta.regressed { "REGRESSION" } else { "ok" };
+    match format {
+        Format::Markdown => {
+            let mut markdown = String::from("| Contender | Scenario | Versions | Before ns/op | After ns/op | Change | Significant | Status |\n| --- | --- | --- | --- | --- | --- | --- | --- |\n");
+            for delta in deltas {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} → {} | {:.1} | {:.1} | {:+.1}% | {} | {} |",
+                    delta.contender, delta.scenario, delta.before_version, delta.after_version,
+                    delta.before, delta.after, delta.change * 100.0, significant(delta), status(delta),
+                ).unwrap();
+            }
+            markdown
+        },
+        Format::Json => {
+            let deltas = deltas
+                .iter()
+                .map(|delta| format!(
+                    "{{\"contender\":{},\"scenario\":{},\"before_version\":{},\"after_version\":{},\"before_ns_per_op\":{},\"after_ns_per_op\":{},\"change\":{},\"significant\":{},\"regressed\":{}}}",
+                    report::json_string(&delta.contender), report::json_string(&delta.scenario),
+                    report::json_string(&delta.before_version), report::json_string(&delta.after_version),
+                    delta.before, delta.after, delta.change,
+                    delta.significant.map(|significant| significant.to_string()).unwrap_or_else(|| "null".to_owned()),
+                    delta.regressed,
+                ))
+                .collect::<Vec<_>>();
+            format!("[{}]", deltas.join(","))
+        },
+        Format::Csv => {
+            let mut csv = String::from("contender,scenario,before_version,after_version,before_ns_per_op,after_ns_per_op,change,significant,regressed\n");
+            for delta in deltas {
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{:.3},{},{}",
+                    report::csv_field(&delta.contender), report::csv_field(&delta.scenario),
+                    report::csv_field(&delta.before_version), report::csv_field(&delta.after_version),
+                    delta.before, delta.after, delta.change,
+                    delta.significant.map(|significant| significant.to_string()).unwrap_or_default(),
+                    delta.regressed,
+                ).unwrap();
+            }
+            csv
+        },
+        Format::Text => {
+            let mut rows = vec![["Contender", "Scenario", "Versions", "Before ns/op", "After ns/op", "Change", "Significant", "Status"].map(str::to_owned).to_vec()];
+            for delta in deltas {
+                rows.push(vec![
+                    delta.contender.clone(),
+                    delta.scenario.clone(),
+                    format!("{} → {}", delta.before_version, delta.after_version),
+                    format!("{:.1}", delta.before),
+                    format!("{:.1}", delta.after),
+                    format!("{:+.1}%", delta.change * 100.0),
+                    significant(delta).to_owned(),
+                    status(delta).to_owned(),
+                ]);
+            }
+            report::text_table(&rows, &[1])
+        },
+    }
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for BaselineError {}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({} → {}): {:.1} → {:.1} ns/op ({:+.1}%)",
+            self.contender, self.scenario, self.before_version, self.after_version, self.before, self.after, self.change * 100.0,
+        )?;
+        if self.significant == Some(false) {
+            write!(f, " not significant")?;
+        }
+        if self.regressed {
+            write!(f, " regression")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    fn timing(contender: &'static str, samples: &[f64]) -> Timing {
+        let summary = crate::bench::stats::Summary::of(samples, 100);
+        Timing {
+            contender,
+            scenario: "usage",
+            iterations: 1,
+            nanos: summary.median,
+            summary: Some(summary),
+            allocations: None,
+            bytes: None,
+        }
+    }
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn render_and_parse() {
+        let baseline = Baseline::record(&Registry::builtin(), &[
+            timing("derive-builder", &[400.5, 410.0, 395.25]),
+            timing("typed-builder", &[150.0]),
+            timing("unknown", &[1.0]),
+        ]);
+
+        assert_eq!(2, baseline.records.len());
+        assert_eq!(Ok(baseline.clone()), Baseline::parse(&baseline.render()));
+    }
+
+    #[test]
+    pub fn records_are_keyed_by_version() {
+        let mut baseline = Baseline::parse("timing foo 1.0.0 usage 100 -\ntiming foo 1.2.0 usage 120 -\ntiming foo 1.10.0 usage 110 -").unwrap();
+        baseline.merge(Baseline::parse("timing foo 1.2.0 usage 125 -\ntiming bar 1.0.0 usage 50 -").unwrap());
+
+        assert_eq!(4, baseline.records.len());
+        assert_eq!(125.0, baseline.find("foo", "usage", "1.2.0").unwrap().1.nanos);
+        assert_eq!("1.10.0", baseline.find("foo", "usage", "2.0.0").unwrap().0.version);
+        assert!(baseline.find("foo", "other", "1.0.0").is_none());
+    }
+
+    #[test]
+    pub fn noise_is_not_a_regression() {
+        let before = Baseline::parse("timing foo 1.0.0 usage 100 90..110\ntiming bar 1.0.0 usage 100 -").unwrap();
+        let after = Baseline::parse("timing foo 1.0.0 usage 115 105..125\ntiming bar 1.0.0 usage 115 -").unwrap();
+
+        let deltas = before.compare(&after, 0.1);
+        let regressed = deltas.iter().filter(|delta| delta.regressed).map(|delta| delta.contender.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["bar"], regressed);
+    }
+
+    #[test]
+    pub fn invalid_baseline() {
+        assert_eq!(
+            Err(BaselineError { line: 2, message: "invalid ns/op 'fast'".to_owned() }),
+            Baseline::parse("timing foo 1.0.0 usage 1\ntiming foo 1.0.0 other fast"),
+        );
+        assert_eq!(
+            Err(BaselineError { line: 1, message: "invalid interval '1-2'".to_owned() }),
+            Baseline::parse("timing foo 1.0.0 usage 1 1-2"),
+        );
+    }
+}
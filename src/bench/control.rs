@@ -0,0 +1,103 @@
+//! Struct-literal control, timed alongside every contender.
+//!
+//! Control builds the same values as contender benchmarks and scaling hooks, written as plain struct literals.
+//! Contender timings are reported as overhead relative to it, telling whether a fluent builder optimizes down to the literal.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::bench::control;
+//! # use rust_benchmark_setter::{derivebuilder,model};
+//! assert_eq!(model::Root::from(derivebuilder::usage()), model::Root::from(control::usage()));
+//! ```
+
+use std::collections::HashMap;
+use crate::registry::{Hook,ScalingHook};
+
+/// Contender name of control timings
+pub const NAME: &str = "literal";
+
+crate::benchmark_model! {
+    #[derive(Clone,Debug,Default,PartialEq)]
+    map: HashMap,
+    option: [],
+    listitems: [],
+    mapitems: [],
+}
+
+/// Demo usage value, as a struct literal
+pub fn usage() -> Root {
+    Root {
+        number: 1,
+        boolean: true,
+        string: "foo".to_owned(),
+        opt_string: Some("bar".to_owned()),
+        opt_item: Some(Item { number: 2, boolean: false, string: "".to_owned(), opt_string: None }),
+        listitems: vec![Item { number: 3, boolean: false, string: "".to_owned(), opt_string: None }],
+        mapitems: HashMap::from([("foobar".to_owned(), Item { number: 4, boolean: false, string: "".to_owned(), opt_string: None })]),
+    }
+}
+
+/// Control of each contender benchmark, by scenario name
+pub fn benchmarks() -> Vec<Hook> {
+    vec![
+        Hook { name: "usage", run: || { std::hint::black_box(usage()); } },
+    ]
+}
+
+/// Root holding `items` list items, as a struct literal
+fn listitems(items: usize) {
+    let listitems = (0..items).map(|number| Item { number: number as u8, ..Item::default() }).collect();
+    std::hint::black_box(Root { listitems, ..Root::default() });
+}
+
+/// Root holding `items` map items, as a struct literal
+fn mapitems(items: usize) {
+    let mapitems = (0..items).map(|number| (number.to_string(), Item { number: number as u8, ..Item::default() })).collect();
+    std::hint::black_box(Root { mapitems, ..Root::default() });
+}
+
+/// Control of each contender [scaling](crate::bench::scaling) hook, by scenario name
+///
+/// `_with` scenarios build the same values through nested builders, so share the same control.
+pub fn scaling() -> Vec<ScalingHook> {
+    vec![
+        ScalingHook { name: "listitems", run: listitems },
+        ScalingHook { name: "listitems_with", run: listitems },
+        ScalingHook { name: "mapitems", run: mapitems },
+        ScalingHook { name: "mapitems_with", run: mapitems },
+    ]
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::model;
+    use crate::registry::Registry;
+
+    #[test]
+    pub fn literal_matches_usages() {
+        let literal = model::Root::from(usage());
+
+        for (contender, actual) in model::tests::usages() {
+            assert_eq!(literal, actual, "{} usage", contender);
+        }
+    }
+
+    #[test]
+    pub fn every_benchmark_is_controlled() {
+        let (controls, scaling_controls) = (benchmarks(), scaling());
+        for contender in Registry::builtin().iter() {
+            for benchmark in contender.benchmarks() {
+                assert!(
+                    controls.iter().any(|control| control.name == benchmark.name),
+                    "No control for {} {}", contender.declaration().name, benchmark.name,
+                );
+            }
+            for hook in contender.scaling() {
+                assert!(
+                    scaling_controls.iter().any(|control| control.name == hook.name),
+                    "No scaling control for {} {}", contender.declaration().name, hook.name,
+                );
+            }
+        }
+    }
+}
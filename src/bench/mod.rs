@@ -0,0 +1,363 @@
+//! Runtime benchmarks, timed with `std` only.
+//!
+//! Each [benchmark hook](crate::registry::Contender::benchmarks) of registered contenders is warmed up, then timed
+//! over repeated samples, summarized by their [statistics](stats). Results are printed by the `bench` binary (`just bench`),
+//! and its CSV output feeds the dashboard (`just report --dashboard ./docs --bench <csv>`).
+//!
+//! A [struct-literal control](control) is timed first, and every contender is reported as overhead relative to it.
+//! When the [counting allocator](alloc) is installed, allocations per operation are reported too.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::bench::{self,Timing};
+//! # use rust_benchmark_setter::bench::stats::Config;
+//! # use rust_benchmark_setter::registry::Registry;
+//! # use std::time::Duration;
+//! let config = Config { warmup: Duration::from_millis(1), sample: Duration::from_micros(100), samples: 5, resamples: 100 };
+//! let timings = bench::run(&Registry::builtin(), &config);
+//!
+//! let timing = timings.iter().find(|timing| timing.contender == "derive-builder").unwrap();
+//! assert_eq!("usage", timing.scenario);
+//! assert!(timing.nanos > 0.0);
+//! ```
+
+use std::fmt::Write;
+use std::time::{Duration,Instant};
+use crate::registry::{Hook,Registry};
+use crate::report::{self,Format};
+use stats::{Config,Summary};
+
+pub mod alloc;
+pub mod baseline;
+pub mod control;
+pub mod scaling;
+pub mod stats;
+
+/// Iterations averaged to count allocations
+const ALLOCATION_ITERATIONS: u64 = 16;
+
+/// Median time of a benchmark hook
+#[derive(Clone,Debug,PartialEq)]
+pub struct Timing {
+    pub contender: &'static str,
+    pub scenario: &'static str,
+    /// Timed loop iterations, per sample
+    pub iterations: u64,
+    /// Nanoseconds per iteration
+    pub nanos: f64,
+    /// Statistics of samples, in nanoseconds per iteration
+    pub summary: Option<Summary>,
+    /// Allocations per iteration, when counted
+    pub allocations: Option<f64>,
+    /// Allocated bytes per iteration, when counted
+    pub bytes: Option<f64>,
+}
+
+/// Time `run`, doubling iterations until lasting at least `target`
+///
+/// Returns iteration count and nanoseconds per iteration.
+pub fn measure<F: Fn()>(run: F, target: Duration) -> (u64, f64) {
+    let mut iterations = 1;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            run();
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= target || iterations >= 1 << 40 {
+            return (iterations, elapsed.as_nanos() as f64 / iterations as f64);
+        }
+        iterations *= 2;
+    }
+}
+
+/// Allocations and bytes per iteration of `run`, `None` unless [counting allocator](alloc) is installed
+pub fn allocations<F: Fn()>(run: F) -> Option<(f64, f64)> {
+    run();
+    let (counts, _) = alloc::count(|| {
+        for _ in 0..ALLOCATION_ITERATIONS {
+            run();
+        }
+    });
+    alloc::is_installed().then(|| (
+        counts.allocations as f64 / ALLOCATION_ITERATIONS as f64,
+        counts.bytes as f64 / ALLOCATION_ITERATIONS as f64,
+    ))
+}
+
+/// Time `benchmark` of `contender`, sampled with `config`
+fn time(contender: &'static str, benchmark: Hook, config: &Config) -> Timing {
+    let (iterations, samples) = stats::sample(benchmark.run, config);
+    let summary = Summary::of(&samples, config.resamples);
+    let allocations = allocations(benchmark.run);
+    Timing {
+        contender,
+        scenario: benchmark.name,
+        iterations,
+        nanos: summary.median,
+        summary: Some(summary),
+        allocations: allocations.map(|(allocations, _)| allocations),
+        bytes: allocations.map(|(_, bytes)| bytes),
+    }
+}
+
+/// Time [control](control) benchmarks, then every benchmark hook of `registry`, sampled with `config`
+pub fn run(registry: &Registry, config: &Config) -> Vec<Timing> {
+    let mut timings = control::benchmarks()
+        .into_iter()
+        .map(|benchmark| time(control::NAME, benchmark, config))
+        .collect::<Vec<_>>();
+    timings.extend(registry
+        .iter()
+        .flat_map(|contender| contender
+            .benchmarks()
+            .into_iter()
+            .map(move |benchmark| time(contender.declaration().name, benchmark, config))
+        )
+    );
+    timings
+}
+
+/// Comparison of each summarized timing with its `reference` timing
+///
+/// `None` for the reference itself, or when not summarized. Otherwise, relative difference and whether it's significant.
+fn versus<'a, F>(timings: &'a [Timing], reference: F) -> Vec<Option<(f64, bool)>> where F: Fn(&'a Timing) -> Option<&'a Timing> {
+    timings
+        .iter()
+        .map(|timing| {
+            let summary = timing.summary.as_ref()?;
+            let reference = reference(timing)?.summary.as_ref()?;
+            (!std::ptr::eq(reference, summary)).then(|| (summary.median / reference.median - 1.0, summary.significant(reference)))
+        })
+        .collect()
+}
+
+/// Comparison of each contender timing with the fastest one of same scenario, control excluded
+fn versus_fastest(timings: &[Timing]) -> Vec<Option<(f64, bool)>> {
+    versus(timings, |timing| {
+        if timing.contender == control::NAME {
+            return None;
+        }
+        timings
+            .iter()
+            .filter(|other| other.scenario == timing.scenario && other.contender != control::NAME && other.summary.is_some())
+            .min_by(|left, right| left.nanos.total_cmp(&right.nanos))
+    })
+}
+
+/// Overhead of each timing, relative to control timing of same scenario
+fn overheads(timings: &[Timing]) -> Vec<Option<(f64, bool)>> {
+    versus(timings, |timing| timings.iter().find(|other| other.contender == control::NAME && other.scenario == timing.scenario))
+}
+
+/// Render `timings`, CSV having `contender,scenario,ns_per_op` leading columns
+///
+/// Each timing is compared with the fastest contender of its scenario, and with the [control](control) as overhead.
+/// Differences are flagged as not significant when confidence intervals overlap.
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::{self,Timing};
+/// # use rust_benchmark_setter::bench::stats::Summary;
+/// # use rust_benchmark_setter::report::Format;
+/// let timings = [
+///     Timing { contender: "literal", scenario: "usage", iterations: 2048, nanos: 10.0, summary: Some(Summary::of(&[10.0], 1)), allocations: None, bytes: None },
+///     Timing { contender: "foo", scenario: "usage", iterations: 1024, nanos: 12.5, summary: None, allocations: Some(3.0), bytes: Some(96.0) },
+///     Timing { contender: "bar", scenario: "usage", iterations: 512, nanos: 20.0, summary: Some(Summary::of(&[20.0], 1)), allocations: None, bytes: None },
+/// ];
+///
+/// assert_eq!(
+///     "contender,scenario,ns_per_op,iterations,allocations_per_op,bytes_per_op,samples,mad,min,max,ci_low,ci_high,vs_fastest,significant,overhead,overhead_significant\n\
+///      literal,usage,10,2048,,,1,0,10,10,10,10,,,,\n\
+///      foo,usage,12.5,1024,3,96,,,,,,,,,,\n\
+///      bar,usage,20,512,,,1,0,20,20,20,20,,,1,true\n",
+///     bench::render(&timings, Format::Csv),
+/// );
+/// ```
+pub fn render(timings: &[Timing], format: Format) -> String {
+    let comparisons = versus_fastest(timings);
+    let overheads = overheads(timings);
+    let number = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let rounded = |value: Option<f64>| value.map(|value| format!("{:.1}", value)).unwrap_or_else(|| "-".to_owned());
+    let spread = |summary: &Option<Summary>| match summary {
+        Some(summary) => [
+            format!("{:.1}", summary.mad),
+            format!("{:.1}", summary.min),
+            format!("{:.1}", summary.max),
+            format!("{:.1}..{:.1}", summary.interval.0, summary.interval.1),
+        ],
+        None => ["-", "-", "-", "-"].map(str::to_owned),
+    };
+    let relative = |comparison: &Option<(f64, bool)>, reference: &str| match comparison {
+        Some((difference, true)) => format!("{:+.1}%", difference * 100.0),
+        Some((difference, false)) => format!("{:+.1}% (not significant)", difference * 100.0),
+        None => reference.to_owned(),
+    };
+    let labels = |timing: &Timing, comparison: &Option<(f64, bool)>, overhead: &Option<(f64, bool)>| match (timing.contender, &timing.summary) {
+        (_, None) => ["-", "-"].map(str::to_owned),
+        (control::NAME, _) => ["-", "control"].map(str::to_owned),
+        _ => [relative(comparison, "fastest"), relative(overhead, "-")],
+    };
+    let entries = timings.iter().zip(comparisons.iter().zip(&overheads)).map(|(timing, (comparison, overhead))| (timing, comparison, overhead));
+    match format {
+        Format::Markdown => {
+            let mut markdown = String::from("| Contender | Scenario | ns/op | MAD | min | max | 95% CI | vs fastest | Overhead | allocations/op | bytes/op |\n| --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- |\n");
+            for (timing, comparison, overhead) in entries {
+                let [mad, min, max, interval] = spread(&timing.summary);
+                let [versus, overhead] = labels(timing, comparison, overhead);
+                writeln!(
+                    markdown,
+                    "| {} | {} | {:.1} | {} | {} | {} | {} | {} | {} | {} | {} |",
+                    timing.contender, timing.scenario, timing.nanos, mad, min, max, interval, versus, overhead,
+                    rounded(timing.allocations), rounded(timing.bytes),
+                ).unwrap();
+            }
+            markdown
+        },
+        Format::Json => {
+            let json = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_else(|| "null".to_owned());
+            let significant = |comparison: &Option<(f64, bool)>| comparison.map(|(_, significant)| significant.to_string()).unwrap_or_else(|| "null".to_owned());
+            let timings = entries
+                .map(|(timing, comparison, overhead)| {
+                    let summary = match &timing.summary {
+                        Some(summary) => format!(
+                            "{{\"samples\":{},\"median\":{},\"mad\":{},\"min\":{},\"max\":{},\"ci\":[{},{}]}}",
+                            summary.samples, summary.median, summary.mad, summary.min, summary.max, summary.interval.0, summary.interval.1,
+                        ),
+                        None => "null".to_owned(),
+                    };
+                    format!(
+                        "{{\"contender\":{},\"scenario\":{},\"iterations\":{},\"ns_per_op\":{},\"allocations_per_op\":{},\"bytes_per_op\":{},\"summary\":{},\"vs_fastest\":{},\"significant\":{},\"overhead\":{},\"overhead_significant\":{}}}",
+                        report::json_string(timing.contender), report::json_string(timing.scenario), timing.iterations, timing.nanos,
+                        json(timing.allocations), json(timing.bytes), summary,
+                        json(comparison.map(|(difference, _)| difference)), significant(comparison),
+                        json(overhead.map(|(difference, _)| difference)), significant(overhead),
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("[{}]", timings.join(","))
+        },
+        Format::Csv => {
+            let significant = |comparison: &Option<(f64, bool)>| comparison.map(|(_, significant)| significant.to_string()).unwrap_or_default();
+            let mut csv = String::from("contender,scenario,ns_per_op,iterations,allocations_per_op,bytes_per_op,samples,mad,min,max,ci_low,ci_high,vs_fastest,significant,overhead,overhead_significant\n");
+            for (timing, comparison, overhead) in entries {
+                let summary = &timing.summary;
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    report::csv_field(timing.contender), report::csv_field(timing.scenario), timing.nanos, timing.iterations,
+                    number(timing.allocations), number(timing.bytes),
+                    summary.as_ref().map(|summary| summary.samples.to_string()).unwrap_or_default(),
+                    number(summary.as_ref().map(|summary| summary.mad)),
+                    number(summary.as_ref().map(|summary| summary.min)),
+                    number(summary.as_ref().map(|summary| summary.max)),
+                    number(summary.as_ref().map(|summary| summary.interval.0)),
+                    number(summary.as_ref().map(|summary| summary.interval.1)),
+                    number(comparison.map(|(difference, _)| difference)), significant(comparison),
+                    number(overhead.map(|(difference, _)| difference)), significant(overhead),
+                ).unwrap();
+            }
+            csv
+        },
+        Format::Text => {
+            let mut rows = vec![["Contender", "Scenario", "ns/op", "MAD", "min", "max", "95% CI", "vs fastest", "Overhead", "allocations/op", "bytes/op"].map(str::to_owned).to_vec()];
+            for (timing, comparison, overhead) in entries {
+                let [mad, min, max, interval] = spread(&timing.summary);
+                let [versus, overhead] = labels(timing, comparison, overhead);
+                rows.push(vec![
+                    timing.contender.to_owned(),
+                    timing.scenario.to_owned(),
+                    format!("{:.1}", timing.nanos),
+                    mad,
+                    min,
+                    max,
+                    interval,
+                    versus,
+                    overhead,
+                    rounded(timing.allocations),
+                    rounded(timing.bytes),
+                ]);
+            }
+            report::text_table(&rows, &[1])
+        },
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::dashboard::Measurement;
+
+    #[test]
+    pub fn measure_reaches_target() {
+        let (iterations, nanos) = measure(|| { std::hint::black_box(1 + 1); }, Duration::from_millis(2));
+
+        assert!(iterations > 1);
+        assert!(nanos * iterations as f64 >= 2_000_000.0);
+    }
+
+    #[cfg(all(feature = "blueprint", feature = "derive_builder"))]
+    #[test]
+    pub fn clone_on_build_allocates_more() {
+        let registry = Registry::builtin();
+        let allocations = |name| {
+            let usage = registry.get(name).expect("Unknown contender").benchmarks()[0];
+            allocations(usage.run).expect("Counting allocator isn't installed")
+        };
+
+        let (builder, builder_bytes) = allocations("blueprint-builder");
+        let (derive, derive_bytes) = allocations("derive-builder");
+        assert!(derive > builder, "derive-builder {} allocations, blueprint-builder {}", derive, builder);
+        assert!(derive_bytes > builder_bytes);
+    }
+
+    #[test]
+    pub fn overlapping_intervals_are_not_significant() {
+        let timing = |contender, samples: &[f64]| Timing {
+            contender,
+            scenario: "usage",
+            iterations: 1,
+            nanos: stats::median(samples),
+            summary: Some(Summary::of(samples, 100)),
+            allocations: None,
+            bytes: None,
+        };
+        let timings = [
+            timing("fast", &[10.0, 10.1, 10.2]),
+            timing("close", &[10.1, 10.2, 10.3]),
+            timing("slow", &[20.0, 20.1, 20.2]),
+        ];
+
+        let comparisons = versus_fastest(&timings);
+        assert_eq!(None, comparisons[0]);
+        assert!(matches!(comparisons[1], Some((_, false))), "{:?}", comparisons[1]);
+        assert!(matches!(comparisons[2], Some((difference, true)) if (difference - 1.0).abs() < 0.01), "{:?}", comparisons[2]);
+        assert!(render(&timings, Format::Text).contains("(not significant)"));
+    }
+
+    #[test]
+    pub fn overhead_is_relative_to_control() {
+        let config = Config { warmup: Duration::from_micros(100), sample: Duration::from_micros(50), samples: 3, resamples: 10 };
+        let timings = run(&Registry::builtin(), &config);
+
+        assert_eq!(control::NAME, timings[0].contender);
+        let overheads = overheads(&timings);
+        assert_eq!(None, overheads[0]);
+        for (timing, overhead) in timings.iter().zip(&overheads).skip(1) {
+            let (overhead, _) = overhead.unwrap_or_else(|| panic!("No overhead for {}", timing.contender));
+            assert_eq!(timing.nanos / timings[0].nanos - 1.0, overhead);
+        }
+        assert_eq!(None, versus_fastest(&timings)[0]);
+    }
+
+    #[test]
+    pub fn csv_feeds_dashboard() {
+        let config = Config { warmup: Duration::from_micros(100), sample: Duration::from_micros(50), samples: 3, resamples: 10 };
+        let timings = run(&Registry::builtin(), &config);
+        let measurements = Measurement::parse_csv(&render(&timings, Format::Csv)).expect("Invalid CSV");
+
+        assert_eq!(timings.len(), measurements.len());
+        for (timing, measurement) in timings.iter().zip(measurements) {
+            assert_eq!(timing.contender, measurement.contender);
+            assert_eq!(timing.nanos, measurement.nanos);
+        }
+    }
+}
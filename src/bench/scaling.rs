@@ -0,0 +1,277 @@
+//! Scaling benchmarks, pushing growing counts of items through collection APIs.
+//!
+//! Each [scaling hook](crate::registry::Contender::scaling) is timed for every item count.
+//! Growth between two counts is reported as an exponent: `1.0` when cost grows linearly with items, `2.0` when quadratic.
+//! A [struct-literal control](super::control) is timed first, and every point reports its overhead relative to control of the same item count.
+//! Results are printed by `just bench --scaling`.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::bench::scaling;
+//! # use rust_benchmark_setter::registry::Registry;
+//! # use std::time::Duration;
+//! let points = scaling::run(&Registry::builtin(), &[10, 100], Duration::from_micros(100));
+//!
+//! let derive = points.iter().filter(|point| point.contender == "derive-builder" && point.scenario == "listitems").collect::<Vec<_>>();
+//! assert_eq!(vec![10, 100], derive.iter().map(|point| point.items).collect::<Vec<_>>());
+//! ```
+
+use std::fmt::Write;
+use std::time::Duration;
+use crate::bench::{self,control};
+use crate::registry::{Registry,ScalingHook};
+use crate::report::{self,Format};
+
+/// Default item counts
+pub const SIZES: &[usize] = &[10, 1_000, 100_000];
+
+/// Mean time of a scaling hook, for a count of items
+#[derive(Clone,Debug,PartialEq)]
+pub struct Point {
+    pub contender: &'static str,
+    pub scenario: &'static str,
+    pub items: usize,
+    /// Timed loop iterations
+    pub iterations: u64,
+    /// Nanoseconds per iteration
+    pub nanos: f64,
+    /// Allocations per iteration, when counted
+    pub allocations: Option<f64>,
+    /// Allocated bytes per iteration, when counted
+    pub bytes: Option<f64>,
+}
+
+/// Growth exponent between two `(items, cost)` points, `None` when undefined
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::scaling;
+/// let linear = scaling::exponent((10, 50.0), (1_000, 5_000.0)).unwrap();
+/// let quadratic = scaling::exponent((10, 50.0), (1_000, 500_000.0)).unwrap();
+///
+/// assert!((linear - 1.0).abs() < 1e-9);
+/// assert!((quadratic - 2.0).abs() < 1e-9);
+/// assert_eq!(None, scaling::exponent((10, 0.0), (1_000, 5_000.0)));
+/// ```
+pub fn exponent(from: (usize, f64), to: (usize, f64)) -> Option<f64> {
+    let exponent = (to.1 / from.1).ln() / (to.0 as f64 / from.0 as f64).ln();
+    exponent.is_finite().then_some(exponent)
+}
+
+/// Time `hook` for each count of `sizes`
+fn time(contender: &'static str, hook: ScalingHook, sizes: &[usize], target: Duration) -> Vec<Point> {
+    sizes
+        .iter()
+        .map(|&items| {
+            let (iterations, nanos) = bench::measure(|| (hook.run)(items), target);
+            let allocations = bench::allocations(|| (hook.run)(items));
+            Point {
+                contender,
+                scenario: hook.name,
+                items,
+                iterations,
+                nanos,
+                allocations: allocations.map(|(allocations, _)| allocations),
+                bytes: allocations.map(|(_, bytes)| bytes),
+            }
+        })
+        .collect()
+}
+
+/// Time [control](control) scaling hooks, then every scaling hook of `registry`, for each count of `sizes`
+pub fn run(registry: &Registry, sizes: &[usize], target: Duration) -> Vec<Point> {
+    let mut points = control::scaling()
+        .into_iter()
+        .flat_map(|hook| time(control::NAME, hook, sizes, target))
+        .collect::<Vec<_>>();
+    points.extend(registry
+        .iter()
+        .flat_map(|contender| contender
+            .scaling()
+            .into_iter()
+            .flat_map(move |hook| time(contender.declaration().name, hook, sizes, target))
+        )
+    );
+    points
+}
+
+/// Overhead of each point, relative to control point of same scenario and item count
+///
+/// `None` for control itself, or when undefined.
+fn overheads(points: &[Point]) -> Vec<Option<f64>> {
+    points
+        .iter()
+        .map(|point| {
+            if point.contender == control::NAME {
+                return None;
+            }
+            let control = points
+                .iter()
+                .find(|other| other.contender == control::NAME && other.scenario == point.scenario && other.items == point.items)?;
+            Some(point.nanos / control.nanos - 1.0).filter(|overhead| overhead.is_finite())
+        })
+        .collect()
+}
+
+/// Time and allocation growth exponents of each point, from previous point of same contender and scenario
+fn growths(points: &[Point]) -> Vec<(Option<f64>, Option<f64>)> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| {
+            let previous = index
+                .checked_sub(1)
+                .map(|previous| &points[previous])
+                .filter(|previous| previous.contender == point.contender && previous.scenario == point.scenario);
+            match previous {
+                Some(previous) => (
+                    exponent((previous.items, previous.nanos), (point.items, point.nanos)),
+                    previous.allocations.zip(point.allocations).and_then(|(from, to)| exponent((previous.items, from), (point.items, to))),
+                ),
+                None => (None, None),
+            }
+        })
+        .collect()
+}
+
+/// Render `points`, with time and allocation growth from previous item count, and overhead relative to control
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::scaling::{self,Point};
+/// # use rust_benchmark_setter::report::Format;
+/// let points = [
+///     Point { contender: "literal", scenario: "listitems", items: 10, iterations: 2048, nanos: 50.0, allocations: Some(1.0), bytes: Some(160.0) },
+///     Point { contender: "foo", scenario: "listitems", items: 10, iterations: 1024, nanos: 100.0, allocations: Some(4.0), bytes: Some(320.0) },
+///     Point { contender: "foo", scenario: "listitems", items: 100, iterations: 128, nanos: 1000.0, allocations: Some(8.0), bytes: Some(3200.0) },
+/// ];
+///
+/// assert_eq!(
+///     "contender,scenario,items,ns_per_op,iterations,allocations_per_op,bytes_per_op,time_growth,allocation_growth,overhead\n\
+///      literal,listitems,10,50,2048,1,160,,,\n\
+///      foo,listitems,10,100,1024,4,320,,,1\n\
+///      foo,listitems,100,1000,128,8,3200,1.000,0.301,\n",
+///     scaling::render(&points, Format::Csv),
+/// );
+/// ```
+pub fn render(points: &[Point], format: Format) -> String {
+    let growths = growths(points);
+    let overheads = overheads(points);
+    let number = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let exponent = |value: Option<f64>| value.map(|value| format!("{:.3}", value)).unwrap_or_default();
+    let rounded = |value: Option<f64>| value.map(|value| format!("{:.1}", value)).unwrap_or_else(|| "-".to_owned());
+    let factor = |value: Option<f64>| value.map(|value| format!("n^{:.2}", value)).unwrap_or_else(|| "-".to_owned());
+    let relative = |point: &Point, overhead: Option<f64>| match (point.contender, overhead) {
+        (control::NAME, _) => "control".to_owned(),
+        (_, Some(overhead)) => format!("{:+.1}%", overhead * 100.0),
+        (_, None) => "-".to_owned(),
+    };
+    let entries = points.iter().zip(growths.iter().zip(&overheads)).map(|(point, ((time, allocation), overhead))| (point, *time, *allocation, *overhead));
+    match format {
+        Format::Markdown => {
+            let mut markdown = String::from("| Contender | Scenario | Items | ns/op | ns/item | Time growth | allocations/op | bytes/op | Allocation growth | Overhead |\n| --- | --- | --- | --- | --- | --- | --- | --- | --- | --- |\n");
+            for (point, time, allocation, overhead) in entries {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} | {:.1} | {:.1} | {} | {} | {} | {} | {} |",
+                    point.contender, point.scenario, point.items, point.nanos, point.nanos / point.items as f64, factor(time),
+                    rounded(point.allocations), rounded(point.bytes), factor(allocation), relative(point, overhead),
+                ).unwrap();
+            }
+            markdown
+        },
+        Format::Json => {
+            let json = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_else(|| "null".to_owned());
+            let points = entries
+                .map(|(point, time, allocation, overhead)| format!(
+                    "{{\"contender\":{},\"scenario\":{},\"items\":{},\"iterations\":{},\"ns_per_op\":{},\"allocations_per_op\":{},\"bytes_per_op\":{},\"time_growth\":{},\"allocation_growth\":{},\"overhead\":{}}}",
+                    report::json_string(point.contender), report::json_string(point.scenario), point.items, point.iterations, point.nanos,
+                    json(point.allocations), json(point.bytes), json(time), json(allocation), json(overhead),
+                ))
+                .collect::<Vec<_>>();
+            format!("[{}]", points.join(","))
+        },
+        Format::Csv => {
+            let mut csv = String::from("contender,scenario,items,ns_per_op,iterations,allocations_per_op,bytes_per_op,time_growth,allocation_growth,overhead\n");
+            for (point, time, allocation, overhead) in entries {
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    report::csv_field(point.contender), report::csv_field(point.scenario), point.items, point.nanos, point.iterations,
+                    number(point.allocations), number(point.bytes), exponent(time), exponent(allocation), number(overhead),
+                ).unwrap();
+            }
+            csv
+        },
+        Format::Text => {
+            let mut rows = vec![["Contender", "Scenario", "Items", "ns/op", "ns/item", "Time growth", "allocations/op", "bytes/op", "Allocation growth", "Overhead"].map(str::to_owned).to_vec()];
+            let mut separators = vec![1];
+            for (index, (point, time, allocation, overhead)) in entries.enumerate() {
+                if index > 0 && (points[index - 1].contender, points[index - 1].scenario) != (point.contender, point.scenario) {
+                    separators.push(rows.len());
+                }
+                rows.push(vec![
+                    point.contender.to_owned(),
+                    point.scenario.to_owned(),
+                    point.items.to_string(),
+                    format!("{:.1}", point.nanos),
+                    format!("{:.1}", point.nanos / point.items as f64),
+                    factor(time),
+                    rounded(point.allocations),
+                    rounded(point.bytes),
+                    factor(allocation),
+                    relative(point, overhead),
+                ]);
+            }
+            report::text_table(&rows, &separators)
+        },
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    pub fn every_size_is_timed() {
+        let registry = Registry::builtin();
+        let points = run(&registry, &[1, 10], Duration::from_micros(50));
+
+        assert_eq!(control::NAME, points[0].contender);
+        for contender in registry.iter() {
+            let name = contender.declaration().name;
+            assert!(points.iter().any(|point| point.contender == name), "{} isn't timed", name);
+        }
+        for pair in points.chunks(2) {
+            assert_eq!([1, 10], [pair[0].items, pair[1].items]);
+            assert_eq!((pair[0].contender, pair[0].scenario), (pair[1].contender, pair[1].scenario));
+        }
+    }
+
+    #[test]
+    pub fn overhead_is_relative_to_control() {
+        let points = run(&Registry::builtin(), &[1, 10], Duration::from_micros(50));
+        let overheads = overheads(&points);
+
+        for (point, overhead) in points.iter().zip(&overheads) {
+            if point.contender == control::NAME {
+                assert_eq!(None, *overhead);
+                continue;
+            }
+            let control = points
+                .iter()
+                .find(|other| other.contender == control::NAME && other.scenario == point.scenario && other.items == point.items)
+                .unwrap_or_else(|| panic!("No control for {} {} {}", point.contender, point.scenario, point.items));
+            assert_eq!(Some(point.nanos / control.nanos - 1.0), *overhead, "{} {} {}", point.contender, point.scenario, point.items);
+        }
+        assert!(render(&points, Format::Text).contains("control"));
+    }
+
+    #[test]
+    pub fn allocations_grow_with_items() {
+        let points = run(&Registry::builtin(), &[10, 1_000], Duration::from_micros(50));
+
+        for pair in points.chunks(2) {
+            let (small, large) = (pair[0].allocations.expect("Counting allocator isn't installed"), pair[1].allocations.unwrap());
+            assert!(large >= small, "{} {}: {} then {} allocations", pair[0].contender, pair[0].scenario, small, large);
+            assert!(pair[1].bytes.unwrap() > 10.0 * pair[0].bytes.unwrap(), "{} {}", pair[0].contender, pair[0].scenario);
+        }
+    }
+}
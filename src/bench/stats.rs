@@ -0,0 +1,201 @@
+//! Sample statistics of benchmark timings, with `std` only.
+//!
+//! A benchmark hook is first run during a warmup, estimating its duration. Iterations per sample are then adapted,
+//! so that each sample lasts [`Config::sample`]. Samples are summarized by their median, median absolute deviation (MAD),
+//! min and max, and a bootstrap confidence interval of the median.
+//!
+//! Two timings differ significantly when their intervals don't overlap:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::bench::stats::Summary;
+//! let fast = Summary::of(&[10.0, 11.0, 10.5, 10.2, 10.8], 1000);
+//! let noisy = Summary::of(&[9.0, 14.0, 10.4, 12.0, 11.0], 1000);
+//! let slow = Summary::of(&[20.0, 21.0, 20.5, 20.2, 20.8], 1000);
+//!
+//! assert!(fast.significant(&slow));
+//! assert!(!fast.significant(&noisy));
+//! ```
+
+use std::time::{Duration,Instant};
+use crate::bench;
+use crate::fuzz::Rng;
+
+/// Seed of bootstrap resampling, so that summaries are reproducible
+const BOOTSTRAP_SEED: u64 = 0x5EED;
+
+/// Confidence level of bootstrap intervals
+pub const CONFIDENCE: f64 = 0.95;
+
+/// Sampling parameters
+#[derive(Clone,Debug,PartialEq)]
+pub struct Config {
+    /// Minimum duration of the warmup loop
+    pub warmup: Duration,
+    /// Target duration of each sample
+    pub sample: Duration,
+    pub samples: usize,
+    /// Bootstrap resamples of the median
+    pub resamples: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(100),
+            sample: Duration::from_millis(25),
+            samples: 20,
+            resamples: 1000,
+        }
+    }
+}
+
+/// Summary of samples
+#[derive(Clone,Debug,PartialEq)]
+pub struct Summary {
+    pub samples: usize,
+    pub median: f64,
+    /// Median absolute deviation from the median
+    pub mad: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Bootstrap confidence interval of the median, at [`CONFIDENCE`] level
+    pub interval: (f64, f64),
+}
+
+/// Median of `sorted` values
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::stats;
+/// assert_eq!(2.0, stats::median(&[1.0, 2.0, 10.0]));
+/// assert_eq!(1.5, stats::median(&[1.0, 2.0]));
+/// ```
+pub fn median(sorted: &[f64]) -> f64 {
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+/// Value below which `fraction` of `sorted` values fall, interpolating between closest ranks
+///
+/// ```rust
+/// # use rust_benchmark_setter::bench::stats;
+/// assert_eq!(1.0, stats::percentile(&[1.0, 2.0, 3.0], 0.0));
+/// assert_eq!(2.5, stats::percentile(&[1.0, 2.0, 3.0], 0.75));
+/// ```
+pub fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn sorted(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut values = values.collect::<Vec<_>>();
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+impl Summary {
+    /// Summarize `samples`, bootstrapping median interval with `resamples`
+    ///
+    /// Panics when `samples` is empty.
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::bench::stats::Summary;
+    /// let summary = Summary::of(&[12.0, 10.0, 11.0, 30.0, 11.0], 1000);
+    ///
+    /// assert_eq!(11.0, summary.median);
+    /// assert_eq!(1.0, summary.mad);
+    /// assert_eq!((10.0, 30.0), (summary.min, summary.max));
+    /// assert!(summary.interval.0 <= 11.0 && 11.0 <= summary.interval.1);
+    /// ```
+    pub fn of(samples: &[f64], resamples: usize) -> Self {
+        assert!(!samples.is_empty(), "No sample to summarize");
+        let values = sorted(samples.iter().copied());
+        let center = median(&values);
+        let mut rng = Rng::new(BOOTSTRAP_SEED);
+        let medians = sorted((0..resamples.max(1)).map(|_| {
+            let resample = sorted((0..values.len()).map(|_| *rng.pick(&values)));
+            median(&resample)
+        }));
+        let tail = (1.0 - CONFIDENCE) / 2.0;
+        Self {
+            samples: values.len(),
+            median: center,
+            mad: median(&sorted(values.iter().map(|value| (value - center).abs()))),
+            min: values[0],
+            max: values[values.len() - 1],
+            interval: (percentile(&medians, tail), percentile(&medians, 1.0 - tail)),
+        }
+    }
+
+    /// Whether confidence intervals overlap
+    pub fn overlaps(&self, other: &Summary) -> bool {
+        self.interval.0 <= other.interval.1 && other.interval.0 <= self.interval.1
+    }
+
+    /// Whether medians differ significantly, intervals being disjoint
+    pub fn significant(&self, other: &Summary) -> bool {
+        !self.overlaps(other)
+    }
+}
+
+/// Sample `run` after a warmup, returning iterations per sample and nanoseconds per iteration of each sample
+pub fn sample<F: Fn()>(run: F, config: &Config) -> (u64, Vec<f64>) {
+    let (_, estimate) = bench::measure(&run, config.warmup);
+    let iterations = ((config.sample.as_nanos() as f64 / estimate.max(1.0)).ceil() as u64).max(1);
+    let samples = (0..config.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                run();
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .collect();
+    (iterations, samples)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    pub fn iterations_adapt_to_sample_duration() {
+        let config = Config { warmup: Duration::from_millis(1), sample: Duration::from_millis(2), samples: 3, resamples: 10 };
+        let (fast, samples) = sample(|| { std::hint::black_box(1 + 1); }, &config);
+        let (slow, _) = sample(|| std::thread::sleep(Duration::from_micros(500)), &config);
+
+        assert_eq!(3, samples.len());
+        assert!(fast > slow, "{} iterations for fast run, {} for slow one", fast, slow);
+        assert!(slow <= 4, "{}", slow);
+    }
+
+    #[test]
+    pub fn bootstrap_is_reproducible() {
+        let samples = [5.0, 7.0, 6.0, 9.0, 5.5, 6.5];
+
+        assert_eq!(Summary::of(&samples, 200), Summary::of(&samples, 200));
+    }
+
+    #[test]
+    pub fn single_sample_has_no_spread() {
+        let summary = Summary::of(&[42.0], 100);
+
+        assert_eq!((42.0, 42.0), summary.interval);
+        assert_eq!(0.0, summary.mad);
+        assert!(summary.overlaps(&summary));
+    }
+
+    #[test]
+    pub fn outliers_barely_move_median() {
+        let clean = Summary::of(&[10.0, 10.5, 11.0, 10.2, 10.8, 10.4, 10.6], 1000);
+        let outlier = Summary::of(&[10.0, 10.5, 11.0, 10.2, 10.8, 10.4, 500.0], 1000);
+
+        assert!((outlier.median - clean.median).abs() < 0.5);
+        assert_eq!(500.0, outlier.max);
+        assert!(outlier.overlaps(&clean));
+    }
+}
@@ -0,0 +1,212 @@
+//! Time benchmark hooks of every contender, printing ns/op and allocations per op.
+//!
+//! ```text
+//! bench [--format markdown|json|csv|text] [--contender <name>]... [--time <ms>] [--samples <count>] [--warmup <ms>]
+//! bench --save <path> | --compare <path> [--threshold <percent>] [OPTIONS]
+//! bench --snapshot <dir> [OPTIONS]
+//! bench --scaling [--items <count>]... [--format markdown|json|csv|text] [--contender <name>]... [--time <ms>]
+//! ```
+
+use std::process::ExitCode;
+use std::time::Duration;
+use rust_benchmark_setter::bench::{self,scaling,Timing};
+use rust_benchmark_setter::bench::alloc::Counting;
+use rust_benchmark_setter::bench::baseline::{self,Baseline};
+use rust_benchmark_setter::bench::stats::Config;
+use rust_benchmark_setter::matrix::snapshot::Snapshot;
+use rust_benchmark_setter::registry::Registry;
+use rust_benchmark_setter::report::Format;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const USAGE: &str = "\
+Usage: bench [OPTIONS]
+       bench --save <path> [OPTIONS]
+       bench --compare <path> [--threshold <percent>] [OPTIONS]
+       bench --snapshot <dir> [OPTIONS]
+       bench --scaling [--items <count>]... [OPTIONS]
+
+Options:
+  --format <format>     Output layout: markdown, json, csv or text [default: text]
+  --contender <name>    Only time given contender (repeatable), e.g. derive-builder
+  --time <ms>           Duration of each benchmark, split into samples, or of each scaling loop [default: 500]
+  --samples <count>     Samples of each benchmark [default: 20]
+  --warmup <ms>         Warmup duration of each benchmark [default: 100]
+  --save <path>         Record timings into given baseline file, keeping other contender versions
+  --compare <path>      Compare timings with given baseline file, failing on regression
+  --threshold <percent> Tolerated slowdown when comparing [default: 10]
+  --snapshot <dir>      Record median timings into contender snapshots of given directory, e.g. snapshots
+  --scaling             Time collection APIs with growing item counts
+  --items <count>       Item count of scaling benchmarks (repeatable) [default: 10, 1000, 100000]
+  -h, --help            Print help
+";
+
+#[derive(Debug)]
+struct Args {
+    format: Format,
+    contenders: Vec<String>,
+    time: Duration,
+    samples: usize,
+    warmup: Duration,
+    save: Option<String>,
+    compare: Option<String>,
+    threshold: f64,
+    snapshot: Option<String>,
+    scaling: bool,
+    items: Vec<usize>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I, registry: &Registry) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        format: Format::Text,
+        contenders: vec![],
+        time: Duration::from_millis(500),
+        samples: 20,
+        warmup: Duration::from_millis(100),
+        save: None,
+        compare: None,
+        threshold: 10.0,
+        snapshot: None,
+        scaling: false,
+        items: vec![],
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for '{}'", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--format" => parsed.format = value()?.parse()?,
+            "--contender" => {
+                let name = value()?;
+                if registry.get(&name).is_none() {
+                    return Err(format!("unknown contender '{}'", name));
+                }
+                parsed.contenders.push(name);
+            },
+            "--time" => {
+                let time = value()?;
+                parsed.time = Duration::from_millis(time.parse().map_err(|_| format!("invalid time '{}'", time))?);
+            },
+            "--samples" => {
+                let samples = value()?;
+                parsed.samples = samples.parse().ok().filter(|samples| *samples > 0).ok_or_else(|| format!("invalid sample count '{}'", samples))?;
+            },
+            "--warmup" => {
+                let warmup = value()?;
+                parsed.warmup = Duration::from_millis(warmup.parse().map_err(|_| format!("invalid warmup '{}'", warmup))?);
+            },
+            "--save" => parsed.save = Some(value()?),
+            "--compare" => parsed.compare = Some(value()?),
+            "--threshold" => {
+                let threshold = value()?;
+                parsed.threshold = threshold.parse().ok().filter(|threshold: &f64| *threshold >= 0.0).ok_or_else(|| format!("invalid threshold '{}'", threshold))?;
+            },
+            "--snapshot" => parsed.snapshot = Some(value()?),
+            "--scaling" => parsed.scaling = true,
+            "--items" => {
+                let items = value()?;
+                parsed.items.push(items.parse().ok().filter(|items| *items > 0).ok_or_else(|| format!("invalid item count '{}'", items))?);
+            },
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    if parsed.scaling && (parsed.save.is_some() || parsed.compare.is_some() || parsed.snapshot.is_some()) {
+        return Err("scaling benchmarks can't be saved, compared nor snapshotted".to_owned());
+    }
+    Ok(Some(parsed))
+}
+
+fn load(path: &str) -> Result<Baseline, String> {
+    let source = std::fs::read_to_string(path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+    Baseline::parse(&source).map_err(|error| format!("invalid baseline '{}': {}", path, error))
+}
+
+/// Merge `recorded` into baseline at `path`, creating it when missing
+fn save(path: &str, recorded: Baseline) -> Result<(), String> {
+    let mut baseline = if std::path::Path::new(path).exists() { load(path)? } else { Baseline::default() };
+    baseline.merge(recorded);
+    std::fs::write(path, baseline.render()).map_err(|error| format!("unable to write '{}': {}", path, error))
+}
+
+/// Record `timings` into snapshot of each registered contender, in `dir`
+///
+/// Contenders without recorded snapshot, such as blueprints, are skipped.
+fn snapshot(dir: &str, registry: &Registry, timings: &[Timing]) -> Result<(), String> {
+    for contender in registry.iter() {
+        let path = std::path::Path::new(dir).join(Snapshot::record(contender.declaration(), &[]).file_name());
+        if !path.exists() {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).map_err(|error| format!("unable to read '{}': {}", path.display(), error))?;
+        let recorded = Snapshot::parse(&source).map_err(|error| format!("invalid snapshot '{}': {}", path.display(), error))?;
+        std::fs::write(&path, recorded.with_timings(timings).render()).map_err(|error| format!("unable to write '{}': {}", path.display(), error))?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut registry = Registry::builtin();
+    let args = match parse_args(std::env::args().skip(1), &registry) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        },
+    };
+    if cfg!(debug_assertions) {
+        eprintln!("warning: timing a debug build, run `just bench` for optimized numbers");
+    }
+
+    if !args.contenders.is_empty() {
+        registry.retain(|contender| args.contenders.iter().any(|name| name == contender.declaration().name));
+    }
+    if args.scaling {
+        let sizes = if args.items.is_empty() { scaling::SIZES } else { &args.items };
+        let points = scaling::run(&registry, sizes, args.time);
+        print!("{}", scaling::render(&points, args.format));
+    } else {
+        let config = Config {
+            warmup: args.warmup,
+            sample: args.time / args.samples as u32,
+            samples: args.samples,
+            ..Config::default()
+        };
+        let timings = bench::run(&registry, &config);
+        let recorded = Baseline::record(&registry, &timings);
+        if let Some(path) = &args.save {
+            if let Err(error) = save(path, recorded.clone()) {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+        if let Some(dir) = &args.snapshot {
+            if let Err(error) = snapshot(dir, &registry, &timings) {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+        if let Some(path) = &args.compare {
+            let deltas = match load(path) {
+                Ok(before) => before.compare(&recorded, args.threshold / 100.0),
+                Err(error) => {
+                    eprintln!("error: {}", error);
+                    return ExitCode::FAILURE;
+                },
+            };
+            print!("{}", baseline::render(&deltas, args.format));
+            if args.format == Format::Json {
+                println!();
+            }
+            return if deltas.iter().any(|delta| delta.regressed) { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+        }
+        print!("{}", bench::render(&timings, args.format));
+    }
+    if args.format == Format::Json {
+        println!();
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,286 @@
+//! Print the builder/setter comparison.
+//!
+//! ```text
+//! report [--format markdown|json|csv|text] [--feature <key>]... [--contender <name>]... [--sort score] [--profile <path>] [--shapes]
+//! report --conformance [--format markdown|json|csv|text]
+//! report --footprint [--format markdown|json|csv|text]
+//! report --codesize <binary> [--format markdown|json|csv|text]
+//! report --scenario <path>
+//! report --diff <before> <after>
+//! report --dashboard <dir> [--profile <path>] [--bench <csv>]
+//! ```
+
+use std::process::ExitCode;
+use rust_benchmark_setter::{codesize,footprint};
+use rust_benchmark_setter::conformance::Grid;
+use rust_benchmark_setter::dashboard::{Dashboard,Measurement};
+use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+use rust_benchmark_setter::matrix::score::Profile;
+use rust_benchmark_setter::matrix::snapshot::Snapshot;
+use rust_benchmark_setter::registry::Registry;
+use rust_benchmark_setter::report::{Format,Report};
+use rust_benchmark_setter::script::Script;
+
+const USAGE: &str = "\
+Usage: report [OPTIONS]
+       report --conformance [--format <format>]
+       report --footprint [--format <format>]
+       report --codesize <binary> [--format <format>]
+       report --scenario <path>
+       report --diff <before> <after>
+       report --dashboard <dir> [--profile <path>] [--bench <csv>]
+
+Options:
+  --format <format>     Output layout: markdown, json, csv or text [default: text]
+  --feature <key>       Only report given feature (repeatable), e.g. collection-field
+  --contender <name>    Only report given contender (repeatable), e.g. derive-builder
+  --sort score          Sort contenders by descending score
+  --profile <path>      Weight features with given profile file, e.g. profiles/collections.profile
+  --shapes              Print support per field shape, for field-level features
+  --conformance         Print pass/fail grid of conformance scenarios
+  --footprint           Print size and alignment of builder types, next to their target struct
+  --codesize <binary>   Print machine-code size of each contender functions, read from given ELF binary, e.g. target/release/bench
+  --scenario <path>     Replay given scenario script against every contender, e.g. scenarios/usage.scenario
+  --diff <before> <after>
+                        Print cells and timings moved between two snapshots, e.g. snapshots/typed-builder-0.16.2.txt
+  --dashboard <dir>     Write HTML dashboard pages into given directory
+  --bench <csv>         Display benchmark results into dashboard
+  -h, --help            Print help
+";
+
+#[derive(Debug)]
+struct Args {
+    format: Format,
+    features: Vec<Feature>,
+    contenders: Vec<String>,
+    sort_by_score: bool,
+    profile: Profile,
+    shapes: bool,
+    conformance: bool,
+    footprint: bool,
+    codesize: Option<String>,
+    scenario: Option<String>,
+    diff: Option<(String, String)>,
+    dashboard: Option<String>,
+    bench: Option<String>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        format: Format::Text,
+        features: vec![],
+        contenders: vec![],
+        sort_by_score: false,
+        profile: Profile::uniform(),
+        shapes: false,
+        conformance: false,
+        footprint: false,
+        codesize: None,
+        scenario: None,
+        diff: None,
+        dashboard: None,
+        bench: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for '{}'", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--format" => parsed.format = value()?.parse()?,
+            "--feature" => parsed.features.push(value()?.parse()?),
+            "--contender" => {
+                let name = value()?;
+                if !CONTENDERS.iter().any(|contender| contender.name == name) {
+                    return Err(format!("unknown contender '{}'", name));
+                }
+                parsed.contenders.push(name);
+            },
+            "--sort" => match value()?.as_str() {
+                "score" => parsed.sort_by_score = true,
+                other => return Err(format!("unknown sort key '{}'", other)),
+            },
+            "--profile" => {
+                let path = value()?;
+                let source = std::fs::read_to_string(&path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+                parsed.profile = Profile::parse(&source).map_err(|error| format!("invalid profile '{}': {}", path, error))?;
+            },
+            "--shapes" => parsed.shapes = true,
+            "--conformance" => parsed.conformance = true,
+            "--footprint" => parsed.footprint = true,
+            "--codesize" => parsed.codesize = Some(value()?),
+            "--scenario" => parsed.scenario = Some(value()?),
+            "--diff" => parsed.diff = Some((value()?, value()?)),
+            "--dashboard" => parsed.dashboard = Some(value()?),
+            "--bench" => parsed.bench = Some(value()?),
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    Ok(Some(parsed))
+}
+
+fn read_snapshot(path: &str) -> Result<Snapshot, String> {
+    let source = std::fs::read_to_string(path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+    Snapshot::parse(&source).map_err(|error| format!("invalid snapshot '{}': {}", path, error))
+}
+
+fn diff(before: &str, after: &str) -> Result<String, String> {
+    let before = read_snapshot(before)?;
+    let after = read_snapshot(after)?;
+    let mut text = format!("{} {} → {} {}\n", before.contender, before.version, after.contender, after.version);
+    let changes = before.diff(&after);
+    let timing_changes = before.timing_diff(&after);
+    if changes.is_empty() && timing_changes.is_empty() {
+        text.push_str("  no change\n");
+    }
+    for change in changes {
+        text.push_str(&format!("  {}\n", change));
+    }
+    for change in timing_changes {
+        text.push_str(&format!("  {}\n", change));
+    }
+    Ok(text)
+}
+
+fn dashboard(dir: &str, profile: Profile, bench: Option<&str>) -> Result<(), String> {
+    let measurements = match bench {
+        Some(path) => {
+            let csv = std::fs::read_to_string(path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+            Measurement::parse_csv(&csv).map_err(|error| format!("invalid benchmark results '{}': {}", path, error))?
+        },
+        None => vec![],
+    };
+    let dashboard = Dashboard::new(CONTENDERS)
+        .with_profile(profile)
+        .with_measurements(measurements);
+    std::fs::create_dir_all(dir).map_err(|error| format!("unable to create '{}': {}", dir, error))?;
+    for (name, html) in dashboard.pages() {
+        let path = std::path::Path::new(dir).join(name);
+        std::fs::write(&path, html).map_err(|error| format!("unable to write '{}': {}", path.display(), error))?;
+    }
+    Ok(())
+}
+
+/// Replay scenario at `path`, returning whether no contender fails
+fn scenario(path: &str) -> Result<bool, String> {
+    let source = std::fs::read_to_string(path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+    let script = Script::parse(&source).map_err(|error| format!("invalid scenario '{}': {}", path, error))?;
+    let registry = Registry::builtin();
+    let mut passed = true;
+    for adapter in registry.adapters() {
+        match script.run(adapter) {
+            Ok(true) => println!("{}: pass", adapter.declaration().name),
+            Ok(false) => println!("{}: n/a", adapter.declaration().name),
+            Err(error) => {
+                println!("{}: FAIL {}", adapter.declaration().name, error);
+                passed = false;
+            },
+        }
+    }
+    Ok(passed)
+}
+
+fn read_codesize(path: &str) -> Result<Vec<codesize::Entry>, String> {
+    let bytes = std::fs::read(path).map_err(|error| format!("unable to read '{}': {}", path, error))?;
+    let entries = codesize::entries(&bytes, &Registry::builtin()).map_err(|error| format!("invalid binary '{}': {}", path, error))?;
+    if entries.is_empty() {
+        return Err(format!("no contender symbol in '{}', is it stripped?", path));
+    }
+    Ok(entries)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        },
+    };
+
+    if let Some((before, after)) = &args.diff {
+        return match diff(before, after) {
+            Ok(changes) => {
+                print!("{}", changes);
+                ExitCode::SUCCESS
+            },
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    if let Some(dir) = &args.dashboard {
+        return match dashboard(dir, args.profile, args.bench.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    if let Some(path) = &args.scenario {
+        return match scenario(path) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    if args.conformance {
+        let grid = Grid::run(&Registry::builtin().adapters());
+        print!("{}", grid.render(args.format));
+        if args.format == Format::Json {
+            println!();
+        }
+        return if grid.failures().is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE };
+    }
+
+    if args.footprint {
+        print!("{}", footprint::render(&footprint::entries(), args.format));
+        if args.format == Format::Json {
+            println!();
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(path) = &args.codesize {
+        return match read_codesize(path) {
+            Ok(entries) => {
+                print!("{}", codesize::render(&entries, args.format));
+                if args.format == Format::Json {
+                    println!();
+                }
+                ExitCode::SUCCESS
+            },
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    let mut report = Report::new(CONTENDERS)
+        .with_features(&args.features)
+        .with_contenders(&args.contenders)
+        .with_profile(args.profile);
+    if args.sort_by_score {
+        report = report.sorted_by_score();
+    }
+    if args.shapes {
+        print!("{}", report.render_shapes(args.format));
+    } else {
+        print!("{}", report.render(args.format));
+    }
+    if args.format == Format::Json {
+        println!();
+    }
+    ExitCode::SUCCESS
+}
@@ -46,6 +46,10 @@
 //! ```
 
 use std::collections::HashMap;
+use crate::matrix::{Declaration,Feature,Support};
+use crate::conformance::{Adapter,Op,Unsupported};
+use crate::model;
+use crate::registry::{Contender,Hook,ScalingHook};
 
 pub trait TakeWithOption<T> {
     fn take_with<FN: FnMut(T)>(&mut self, f: FN);
@@ -59,28 +63,15 @@ impl<T> TakeWithOption<T> for Option<T> {
     }
 }
 
-/// Root data structure
-#[derive(Debug,Default,PartialEq)]
-pub struct Root {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    pub opt_string: Option<String>,
-    pub opt_item: Option<Item>,
-    pub listitems: Vec<Item>,
-    pub mapitems: HashMap<String, Item>,
+crate::benchmark_model! {
+    #[derive(Clone,Debug,Default,PartialEq)]
+    map: HashMap,
+    option: [],
+    listitems: [],
+    mapitems: [],
 }
 
-/// Sub-item for Root data structure
-#[derive(Debug,Default,PartialEq)]
-pub struct Item {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    pub opt_string: Option<String>,
-}
-
-#[derive(Default)]
+#[derive(Clone,Default)]
 pub struct RootBuilder {
     number: Option<u8>,
     boolean: Option<bool>,
@@ -91,7 +82,7 @@ pub struct RootBuilder {
     mapitems: Option<HashMap<String, Item>>,
 }
 
-#[derive(Default)]
+#[derive(Clone,Default)]
 pub struct ItemBuilder {
     number: Option<u8>,
 }
@@ -108,13 +99,23 @@ impl Item {
     }
 }
 
-impl Into<Root> for &mut RootBuilder {
-    fn into(self) -> Root {
-        self.build()
+impl From<&mut RootBuilder> for Root {
+    fn from(builder: &mut RootBuilder) -> Self {
+        builder.build()
     }
 }
 
 impl RootBuilder {
+    /// Build, draining the builder: a second call builds defaults
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::blueprint::builder::Root;
+    /// let mut builder = Root::builder();
+    /// builder.number(1);
+    ///
+    /// assert_eq!(1, builder.build().number);
+    /// assert_eq!(0, builder.build().number);
+    /// ```
     pub fn build(&mut self) -> Root {
         let mut root = Root::default();
         self.number.take_with(|number| root.number = number);
@@ -127,6 +128,25 @@ impl RootBuilder {
         root
     }
 
+    /// Build, cloning values so that the builder can be reused
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::blueprint::builder::Root;
+    /// let mut builder = Root::builder();
+    /// builder.number(1).listitem_with(|i| i.number(2));
+    ///
+    /// let first = builder.build_cloned();
+    /// builder.number(3);
+    /// let second = builder.build_cloned();
+    ///
+    /// assert_eq!(1, first.number);
+    /// assert_eq!(3, second.number);
+    /// assert_eq!(first.listitems, second.listitems);
+    /// ```
+    pub fn build_cloned(&self) -> Root {
+        self.clone().build()
+    }
+
     /// ```rust
     /// # use rust_benchmark_setter::blueprint::builder::Root;
     /// let root = Root::builder()
@@ -244,7 +264,7 @@ impl RootBuilder {
     /// );
     /// ```
     pub fn listitem<I: Into<Item>>(&mut self, item: I) -> &mut Self {
-        self.listitems.get_or_insert_with(|| Vec::new()).push(item.into());
+        self.listitems.get_or_insert_with(Vec::new).push(item.into());
         self
     }
 
@@ -293,7 +313,7 @@ impl RootBuilder {
     /// );
     /// ```
     pub fn mapitem<S: Into<String>, I: Into<Item>>(&mut self, key: S, item: I) -> &mut Self {
-        self.mapitems.get_or_insert_with(|| HashMap::new()).insert(key.into(), item.into());
+        self.mapitems.get_or_insert_with(HashMap::new).insert(key.into(), item.into());
         self
     }
 
@@ -330,6 +350,11 @@ impl ItemBuilder {
         item
     }
 
+    /// Build, leaving the builder untouched
+    pub fn build_cloned(&self) -> Item {
+        self.clone().build()
+    }
+
     /// ```rust
     /// # use rust_benchmark_setter::blueprint::builder::Item;
     /// let item = Item::builder()
@@ -343,3 +368,128 @@ impl ItemBuilder {
         self
     }
 }
+
+/// Declared support levels
+pub const DECLARATION: Declaration = Declaration {
+    name: "blueprint-builder",
+    version: env!("CARGO_PKG_VERSION"),
+    module: "blueprint/builder",
+    claims: &[
+        (Feature::BuilderFunction, Support::Supported),
+        (Feature::IntoField, Support::Supported),
+        (Feature::OptionField, Support::Supported),
+        (Feature::DefaultStruct, Support::Supported),
+        (Feature::CollectionField, Support::Supported),
+        (Feature::BuilderField, Support::Supported),
+        (Feature::IntoBuilder, Support::Supported),
+        (Feature::ChainCall, Support::Supported),
+        (Feature::BuilderCustomization, Support::Supported),
+        (Feature::BuilderReuse, Support::Supported),
+    ],
+    shapes: &[],
+};
+
+/// Demo usage, building every field
+pub fn usage() -> Root {
+    Root::builder()
+        .number(1)
+        .boolean(true)
+        .string("foo")
+        .opt_string("bar")
+        .opt_item_with(|i| i.number(2))
+        .listitem_with(|i| i.number(3))
+        .mapitem_with("foobar", |i| i.number(4))
+        .build()
+}
+
+/// Registered [`Contender`]
+pub struct Demo;
+
+impl Contender for Demo {
+    fn declaration(&self) -> &Declaration {
+        &DECLARATION
+    }
+
+    fn scenarios(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || assert_eq!(
+                Root {
+                    number: 1,
+                    boolean: true,
+                    string: "foo".to_owned(),
+                    opt_string: Some("bar".to_owned()),
+                    opt_item: Some(Item { number: 2, ..Default::default() }),
+                    listitems: vec![Item { number: 3, ..Default::default() }],
+                    mapitems: HashMap::from([("foobar".to_owned(), Item { number: 4, ..Default::default() })]),
+                },
+                usage(),
+            ) },
+            Hook { name: "default", run: || assert_eq!(
+                Root {
+                    number: 0,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                    opt_item: None,
+                    listitems: vec![],
+                    mapitems: HashMap::new(),
+                },
+                Root::builder().build(),
+            ) },
+        ]
+    }
+
+    fn benchmarks(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || { std::hint::black_box(usage()); } },
+        ]
+    }
+
+    fn scaling(&self) -> Vec<ScalingHook> {
+        vec![
+            ScalingHook { name: "listitems", run: |items| {
+                let mut builder = Root::builder();
+                for number in 0..items {
+                    builder.listitem(Item::builder().number(number as u8).build());
+                }
+                std::hint::black_box(builder.build());
+            } },
+            ScalingHook { name: "listitems_with", run: |items| {
+                let mut builder = Root::builder();
+                for number in 0..items {
+                    builder.listitem_with(|i| i.number(number as u8));
+                }
+                std::hint::black_box(builder.build());
+            } },
+            ScalingHook { name: "mapitems_with", run: |items| {
+                let mut builder = Root::builder();
+                for number in 0..items {
+                    builder.mapitem_with(number.to_string(), |i| i.number(number as u8));
+                }
+                std::hint::black_box(builder.build());
+            } },
+        ]
+    }
+
+    fn adapter(&self) -> Option<&dyn Adapter> {
+        Some(self)
+    }
+}
+
+impl Adapter for Demo {
+    fn apply(&self, ops: &[Op]) -> Result<model::Root, Unsupported> {
+        let mut builder = Root::builder();
+        for op in ops {
+            match op {
+                Op::Number(number) => builder.number(*number),
+                Op::Boolean(boolean) => builder.boolean(*boolean),
+                Op::String(string) => builder.string(string.as_str()),
+                Op::OptString(string) => builder.opt_string(string.as_str()),
+                Op::OptItem(number) => builder.opt_item_with(|i| i.number(*number)),
+                Op::ListItem(number) => builder.listitem_with(|i| i.number(*number)),
+                Op::MapItem(key, number) => builder.mapitem_with(key.as_str(), |i| i.number(*number)),
+            };
+        }
+        Ok(builder.build().into())
+    }
+}
@@ -48,26 +48,17 @@
 //! ```
 
 use std::collections::HashMap;
+use crate::matrix::{Declaration,Feature,Support};
+use crate::conformance::{Adapter,Op,Unsupported};
+use crate::model;
+use crate::registry::{Contender,Hook,ScalingHook};
 
-/// Root data structure
-#[derive(Debug,Default,PartialEq)]
-pub struct Root {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    pub opt_string: Option<String>,
-    pub opt_item: Option<Item>,
-    pub listitems: Vec<Item>,
-    pub mapitems: HashMap<String, Item>,
-}
-
-/// Sub-item for Root data structure
-#[derive(Debug,Default,PartialEq)]
-pub struct Item {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    pub opt_string: Option<String>,
+crate::benchmark_model! {
+    #[derive(Debug,Default,PartialEq)]
+    map: HashMap,
+    option: [],
+    listitems: [],
+    mapitems: [],
 }
 
 impl Root {
@@ -292,3 +283,130 @@ impl Item {
         self
     }
 }
+
+/// Declared support levels
+pub const DECLARATION: Declaration = Declaration {
+    name: "blueprint-setter",
+    version: env!("CARGO_PKG_VERSION"),
+    module: "blueprint/setter",
+    claims: &[
+        (Feature::BuilderFunction, Support::Unsupported),
+        (Feature::IntoField, Support::Supported),
+        (Feature::OptionField, Support::Supported),
+        (Feature::DefaultStruct, Support::Supported),
+        (Feature::CollectionField, Support::Supported),
+        (Feature::BuilderField, Support::Supported),
+        (Feature::IntoBuilder, Support::Unsupported),
+        (Feature::ChainCall, Support::Supported),
+        (Feature::BuilderCustomization, Support::Supported),
+        (Feature::BuilderReuse, Support::Unsupported),
+    ],
+    shapes: &[],
+};
+
+/// Demo usage, building every field
+pub fn usage() -> Root {
+    Root::default()
+        .with(|r| { r
+            .set_number(1)
+            .set_boolean(true)
+            .set_string("foo")
+            .set_opt_string("bar")
+            .set_opt_item_with_default(|i| { i.set_number(2); })
+            .push_listitem_with_default(|i| { i.set_number(3); })
+            .push_mapitem_with_default("foobar", |i| { i.set_number(4); })
+            ;
+        })
+}
+
+/// Registered [`Contender`]
+pub struct Demo;
+
+impl Contender for Demo {
+    fn declaration(&self) -> &Declaration {
+        &DECLARATION
+    }
+
+    fn scenarios(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || assert_eq!(
+                Root {
+                    number: 1,
+                    boolean: true,
+                    string: "foo".to_owned(),
+                    opt_string: Some("bar".to_owned()),
+                    opt_item: Some(Item { number: 2, ..Default::default() }),
+                    listitems: vec![Item { number: 3, ..Default::default() }],
+                    mapitems: HashMap::from([("foobar".to_owned(), Item { number: 4, ..Default::default() })]),
+                },
+                usage(),
+            ) },
+            Hook { name: "default", run: || assert_eq!(
+                Root {
+                    number: 0,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                    opt_item: None,
+                    listitems: vec![],
+                    mapitems: HashMap::new(),
+                },
+                Root::default(),
+            ) },
+        ]
+    }
+
+    fn benchmarks(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || { std::hint::black_box(usage()); } },
+        ]
+    }
+
+    fn scaling(&self) -> Vec<ScalingHook> {
+        vec![
+            ScalingHook { name: "listitems", run: |items| {
+                let mut root = Root::default();
+                for number in 0..items {
+                    root.push_listitem(Item::default().with(|i| { i.set_number(number as u8); }));
+                }
+                std::hint::black_box(root);
+            } },
+            ScalingHook { name: "listitems_with", run: |items| {
+                let mut root = Root::default();
+                for number in 0..items {
+                    root.push_listitem_with_default(|i| { i.set_number(number as u8); });
+                }
+                std::hint::black_box(root);
+            } },
+            ScalingHook { name: "mapitems_with", run: |items| {
+                let mut root = Root::default();
+                for number in 0..items {
+                    root.push_mapitem_with_default(number.to_string(), |i| { i.set_number(number as u8); });
+                }
+                std::hint::black_box(root);
+            } },
+        ]
+    }
+
+    fn adapter(&self) -> Option<&dyn Adapter> {
+        Some(self)
+    }
+}
+
+impl Adapter for Demo {
+    fn apply(&self, ops: &[Op]) -> Result<model::Root, Unsupported> {
+        let mut root = Root::default();
+        for op in ops {
+            match op {
+                Op::Number(number) => root.set_number(*number),
+                Op::Boolean(boolean) => root.set_boolean(*boolean),
+                Op::String(string) => root.set_string(string.as_str()),
+                Op::OptString(string) => root.set_opt_string(string.as_str()),
+                Op::OptItem(number) => root.set_opt_item_with_default(|i| { i.set_number(*number); }),
+                Op::ListItem(number) => root.push_listitem_with_default(|i| { i.set_number(*number); }),
+                Op::MapItem(key, number) => root.push_mapitem_with_default(key.as_str(), |i| { i.set_number(*number); }),
+            };
+        }
+        Ok(root.into())
+    }
+}
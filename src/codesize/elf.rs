@@ -0,0 +1,313 @@
+//! Minimal ELF symbol table reader, and legacy Rust symbol demangling.
+//!
+//! Only what code size attribution needs is read: sized function symbols of `.symtab` section,
+//! from 32 or 64-bit files of either endianness.
+
+use std::error::Error;
+use std::fmt;
+
+/// Section type of a symbol table
+const SHT_SYMTAB: u32 = 2;
+
+/// Symbol type of a function
+const STT_FUNC: u8 = 2;
+
+/// Function symbol
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Symbol {
+    /// Mangled name
+    pub name: String,
+    pub address: u64,
+    /// Machine code size, in bytes
+    pub size: u64,
+}
+
+/// Malformed ELF file
+#[derive(Clone,Debug,PartialEq)]
+pub struct ElfError {
+    /// Byte offset of malformed data
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Bounds-checked reader of ELF fields
+struct Reader<'a> {
+    bytes: &'a [u8],
+    wide: bool,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, offset: usize, len: usize) -> Result<&'a [u8], ElfError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or_else(|| ElfError { offset, message: format!("truncated file, {} bytes expected", len) })
+    }
+
+    fn uint(&self, offset: usize, len: usize) -> Result<u64, ElfError> {
+        let bytes = self.slice(offset, len)?;
+        let fold = |value: u64, byte: &u8| value << 8 | *byte as u64;
+        Ok(if self.big_endian { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) })
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, ElfError> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<usize, ElfError> {
+        Ok(self.uint(offset, 2)? as usize)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u64, ElfError> {
+        self.uint(offset, 4)
+    }
+
+    /// Address-sized field
+    fn word(&self, offset: usize) -> Result<u64, ElfError> {
+        self.uint(offset, if self.wide { 8 } else { 4 })
+    }
+
+    /// Nul-terminated string at `offset` of string table `table`
+    fn string(&self, table: &Section, offset: u64) -> Result<String, ElfError> {
+        let start = (table.offset + offset) as usize;
+        let bytes = self.slice(start, table.size.saturating_sub(offset) as usize)?;
+        let end = bytes.iter().position(|byte| *byte == 0).ok_or_else(|| ElfError { offset: start, message: "unterminated string".to_owned() })?;
+        Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+}
+
+struct Section {
+    kind: u32,
+    offset: u64,
+    size: u64,
+    link: usize,
+    entry_size: u64,
+}
+
+impl<'a> Reader<'a> {
+    fn section(&self, offset: usize) -> Result<Section, ElfError> {
+        Ok(if self.wide {
+            Section {
+                kind: self.u32(offset + 4)? as u32,
+                offset: self.word(offset + 24)?,
+                size: self.word(offset + 32)?,
+                link: self.u32(offset + 40)? as usize,
+                entry_size: self.word(offset + 56)?,
+            }
+        } else {
+            Section {
+                kind: self.u32(offset + 4)? as u32,
+                offset: self.word(offset + 16)?,
+                size: self.word(offset + 20)?,
+                link: self.u32(offset + 24)? as usize,
+                entry_size: self.word(offset + 36)?,
+            }
+        })
+    }
+}
+
+/// Sized function symbols of `.symtab` section, empty when stripped
+pub fn symbols(bytes: &[u8]) -> Result<Vec<Symbol>, ElfError> {
+    if !bytes.starts_with(b"\x7fELF") {
+        return Err(ElfError { offset: 0, message: "not an ELF file".to_owned() });
+    }
+    let mut reader = Reader { bytes, wide: false, big_endian: false };
+    reader.wide = match reader.u8(4)? {
+        1 => false,
+        2 => true,
+        class => return Err(ElfError { offset: 4, message: format!("unknown class {}", class) }),
+    };
+    reader.big_endian = match reader.u8(5)? {
+        1 => false,
+        2 => true,
+        encoding => return Err(ElfError { offset: 5, message: format!("unknown data encoding {}", encoding) }),
+    };
+    let (table, entry_size, count) = if reader.wide { (0x28, 0x3A, 0x3C) } else { (0x20, 0x2E, 0x30) };
+    let (table, entry_size, count) = (reader.word(table)? as usize, reader.u16(entry_size)?, reader.u16(count)?);
+    let sections = (0..count)
+        .map(|index| reader.section(table + index * entry_size))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut symbols = vec![];
+    for symtab in sections.iter().filter(|section| section.kind == SHT_SYMTAB) {
+        let strtab = sections.get(symtab.link).ok_or_else(|| ElfError { offset: table, message: format!("no string table {}", symtab.link) })?;
+        let symbol_size = if reader.wide { 24 } else { 16 };
+        let entry_size = if symtab.entry_size == 0 { symbol_size } else { symtab.entry_size as usize };
+        for index in 0..(symtab.size as usize / entry_size) {
+            let offset = symtab.offset as usize + index * entry_size;
+            let (info, address, size) = if reader.wide {
+                (reader.u8(offset + 4)?, reader.word(offset + 8)?, reader.word(offset + 16)?)
+            } else {
+                (reader.u8(offset + 12)?, reader.word(offset + 4)?, reader.word(offset + 8)?)
+            };
+            if info & 0xf != STT_FUNC || size == 0 {
+                continue;
+            }
+            let name = reader.string(strtab, reader.u32(offset)?)?;
+            symbols.push(Symbol { name, address, size });
+        }
+    }
+    Ok(symbols)
+}
+
+/// Demangle legacy Rust symbol `name`, without its hash. Other names are returned as is.
+///
+/// ```rust
+/// # use rust_benchmark_setter::codesize::elf;
+/// assert_eq!(
+///     "rust_benchmark_setter::derivebuilder::RootBuilder::build",
+///     elf::demangle("_ZN21rust_benchmark_setter13derivebuilder11RootBuilder5build17h0123456789abcdefE"),
+/// );
+/// assert_eq!(
+///     "<rust_benchmark_setter::model::Root as core::clone::Clone>::clone",
+///     elf::demangle("_ZN73_$LT$rust_benchmark_setter..model..Root$u20$as$u20$core..clone..Clone$GT$5clone17h0123456789abcdefE"),
+/// );
+/// assert_eq!("main", elf::demangle("main"));
+/// ```
+pub fn demangle(name: &str) -> String {
+    let Some(mut rest) = name.strip_prefix("_ZN").or_else(|| name.strip_prefix("__ZN")) else {
+        return name.to_owned();
+    };
+    let mut idents = vec![];
+    while !rest.starts_with('E') {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let Some(len) = rest[..digits].parse::<usize>().ok().filter(|len| digits + len <= rest.len()) else {
+            return name.to_owned();
+        };
+        idents.push(&rest[digits..digits + len]);
+        rest = &rest[digits + len..];
+    }
+    let hashed = |ident: &&str| ident.len() == 17 && ident.starts_with('h') && ident[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if idents.len() > 1 && idents.last().is_some_and(hashed) {
+        idents.pop();
+    }
+    idents.iter().map(|ident| unescape(ident)).collect::<Vec<_>>().join("::")
+}
+
+/// Decode `$..$` escapes and `..` path separators of a legacy identifier
+fn unescape(ident: &str) -> String {
+    let mut ident = ident.strip_prefix("_$").map(|rest| format!("${}", rest)).unwrap_or_else(|| ident.to_owned());
+    let mut decoded = String::new();
+    while !ident.is_empty() {
+        if let Some(rest) = ident.strip_prefix("..") {
+            decoded.push_str("::");
+            ident = rest.to_owned();
+            continue;
+        }
+        if let Some((escape, rest)) = ident.strip_prefix('$').and_then(|rest| rest.split_once('$')) {
+            let replacement = match escape {
+                "SP" => Some('@'),
+                "BP" => Some('*'),
+                "RF" => Some('&'),
+                "LT" => Some('<'),
+                "GT" => Some('>'),
+                "LP" => Some('('),
+                "RP" => Some(')'),
+                "C" => Some(','),
+                _ => escape.strip_prefix('u').and_then(|code| u32::from_str_radix(code, 16).ok()).and_then(char::from_u32),
+            };
+            if let Some(replacement) = replacement {
+                decoded.push(replacement);
+                ident = rest.to_owned();
+                continue;
+            }
+        }
+        let c = ident.chars().next().unwrap();
+        decoded.push(c);
+        ident = ident[c.len_utf8()..].to_owned();
+    }
+    decoded
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "offset {}: {}", self.offset, self.message)
+    }
+}
+
+impl Error for ElfError {}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Little-endian ELF64 file, with a symbol table of `(name, type, size)` entries
+    pub fn elf64(entries: &[(&str, u8, u64)]) -> Vec<u8> {
+        let mut strtab = vec![0u8];
+        let mut symtab = vec![0u8; 24];
+        for (index, (name, kind, size)) in entries.iter().enumerate() {
+            let mut symbol = vec![];
+            symbol.extend((strtab.len() as u32).to_le_bytes());
+            symbol.extend([*kind, 0, 1, 0]);
+            symbol.extend((0x1000 + 0x100 * index as u64).to_le_bytes());
+            symbol.extend(size.to_le_bytes());
+            symtab.extend(symbol);
+            strtab.extend(name.as_bytes());
+            strtab.push(0);
+        }
+        let section = |kind: u32, offset: usize, size: usize, link: u32, entry_size: u64| {
+            let mut header = vec![0u8; 64];
+            header[4..8].copy_from_slice(&kind.to_le_bytes());
+            header[24..32].copy_from_slice(&(offset as u64).to_le_bytes());
+            header[32..40].copy_from_slice(&(size as u64).to_le_bytes());
+            header[40..44].copy_from_slice(&link.to_le_bytes());
+            header[56..64].copy_from_slice(&entry_size.to_le_bytes());
+            header
+        };
+        let mut bytes = vec![0u8; 64];
+        bytes[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        let symtab_offset = bytes.len();
+        bytes.extend(&symtab);
+        let strtab_offset = bytes.len();
+        bytes.extend(&strtab);
+        let table = bytes.len();
+        bytes.extend(section(0, 0, 0, 0, 0));
+        bytes.extend(section(SHT_SYMTAB, symtab_offset, symtab.len(), 2, 24));
+        bytes.extend(section(3, strtab_offset, strtab.len(), 0, 0));
+        bytes[0x28..0x30].copy_from_slice(&(table as u64).to_le_bytes());
+        bytes[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+        bytes[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    pub fn reads_sized_functions() {
+        let bytes = elf64(&[("foo", STT_FUNC, 42), ("data", 1, 8), ("empty", STT_FUNC, 0), ("bar", STT_FUNC, 7)]);
+
+        assert_eq!(
+            vec![
+                Symbol { name: "foo".to_owned(), address: 0x1000, size: 42 },
+                Symbol { name: "bar".to_owned(), address: 0x1300, size: 7 },
+            ],
+            symbols(&bytes).unwrap(),
+        );
+    }
+
+    #[test]
+    pub fn rejects_malformed_files() {
+        assert_eq!(Err(ElfError { offset: 0, message: "not an ELF file".to_owned() }), symbols(b"MZ"));
+
+        let bytes = elf64(&[("foo", STT_FUNC, 42)]);
+        assert!(symbols(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    pub fn demangles_escapes() {
+        assert_eq!(
+            "core::ptr::drop_in_place<[(alloc::string::String,u8); 1]>",
+            demangle("_ZN4core3ptr76drop_in_place$LT$$u5b$$LP$alloc..string..String$C$u8$RP$$u3b$$u20$1$u5d$$GT$17h0123456789abcdefE"),
+        );
+        assert_eq!("foo::{{closure}}", demangle("_ZN3foo28_$u7b$$u7b$closure$u7d$$u7d$17h0123456789abcdefE"));
+        assert_eq!("_ZN3foo", demangle("_ZN3foo"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn reads_current_executable() {
+        let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let names = symbols(&bytes).unwrap().iter().map(|symbol| demangle(&symbol.name)).collect::<Vec<_>>();
+
+        assert!(names.iter().any(|name| name == "rust_benchmark_setter::bench::control::usage"), "{} symbols", names.len());
+    }
+}
@@ -0,0 +1,344 @@
+//! Machine-code size of each contender, read from the symbol table of a compiled artifact.
+//!
+//! Sized function symbols are read by the in-crate [`elf`] reader, demangled, then attributed to the contender
+//! whose module they belong to, as declared by [`Declaration::module`](crate::matrix::Declaration::module):
+//! * `builder`: inherent functions of contender types, e.g. `RootBuilder::build` or setters,
+//!   and any function of `*Builder` types, e.g. derived trait implementations or drop glue
+//! * `usage`: the demo `usage` function, building a value through the whole API
+//! * `other`: remaining functions mentioning the contender module, e.g. trait implementations of target structs
+//! * `harness`: functions only serving this crate's benchmark harness, left out of totals: the `Demo` contender and its hooks,
+//!   conformance adapter, and conversions into the canonical [model](crate::model)
+//!
+//! Generic functions only appear once monomorphized and not inlined, so an optimized artifact is expected:
+//! `just codesize` reports the release `bench` binary. Stripped artifacts have no symbol to attribute.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::codesize::{self,Kind};
+//! # use rust_benchmark_setter::codesize::elf::Symbol;
+//! # use rust_benchmark_setter::registry::Registry;
+//! let symbol = |name: &str, size| Symbol { name: name.to_owned(), address: 0, size };
+//! let entries = codesize::attribute(&[
+//!     symbol("_ZN21rust_benchmark_setter13derivebuilder11RootBuilder5build17h0123456789abcdefE", 1465),
+//!     symbol("_ZN21rust_benchmark_setter13derivebuilder5usage17h0123456789abcdefE", 1127),
+//! ], &codesize::targets(&Registry::builtin()));
+//!
+//! assert_eq!("derivebuilder::RootBuilder::build", entries[0].symbol);
+//! assert_eq!((Kind::Builder, 1465), (entries[0].kind, entries[0].size));
+//! assert_eq!(Kind::Usage, entries[1].kind);
+//! ```
+
+pub mod elf;
+
+use std::fmt::Write;
+use crate::bench::control;
+use crate::registry::Registry;
+use crate::report::{self,Format};
+use self::elf::{ElfError,Symbol};
+
+/// Path prefix of this crate symbols
+const CRATE: &str = "rust_benchmark_setter::";
+
+/// Harness modules, whose mention makes a function part of the harness
+const HARNESS_MODULES: &[&str] = &["conformance::", "model::", "registry::"];
+
+/// Harness items of contender modules
+const HARNESS_ITEMS: &[&str] = &["Demo", "tests"];
+
+/// Part of a contender's code a symbol belongs to
+#[derive(Clone,Copy,Debug,Eq,Ord,PartialEq,PartialOrd)]
+pub enum Kind {
+    Builder,
+    Usage,
+    Other,
+    Harness,
+}
+
+impl Kind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Builder => "builder",
+            Kind::Usage => "usage",
+            Kind::Other => "other",
+            Kind::Harness => "harness",
+        }
+    }
+}
+
+/// Function attributed to a contender
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Entry {
+    pub contender: &'static str,
+    pub kind: Kind,
+    /// Demangled name, without crate path
+    pub symbol: String,
+    /// Machine code size, in bytes
+    pub size: u64,
+}
+
+/// Code size of a contender
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Total {
+    pub contender: &'static str,
+    /// Count of builder functions
+    pub functions: usize,
+    pub builder: u64,
+    pub usage: u64,
+    pub other: u64,
+    /// Harness functions, left out of total
+    pub harness: u64,
+}
+
+impl Total {
+    /// Contender code size, harness excluded
+    pub fn total(&self) -> u64 {
+        self.builder + self.usage + self.other
+    }
+}
+
+/// Contender name and module path prefix of registered contenders, and of struct-literal control
+///
+/// ```rust
+/// # use rust_benchmark_setter::codesize;
+/// # use rust_benchmark_setter::registry::Registry;
+/// let targets = codesize::targets(&Registry::builtin());
+///
+/// assert!(targets.contains(&("blueprint-builder", "rust_benchmark_setter::blueprint::builder::".to_owned())));
+/// assert!(targets.contains(&("literal", "rust_benchmark_setter::bench::control::".to_owned())));
+/// ```
+pub fn targets(registry: &Registry) -> Vec<(&'static str, String)> {
+    let mut targets = vec![(control::NAME, format!("{}bench::control::", CRATE))];
+    for declaration in registry.declarations() {
+        targets.push((declaration.name, format!("{}{}::", CRATE, declaration.module.replace('/', "::"))));
+    }
+    targets
+}
+
+/// Leading identifier of `path`
+fn ident(path: &str) -> &str {
+    let end = path.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(path.len());
+    &path[..end]
+}
+
+/// Kind of `name` function for contender module at `prefix`, if mentioned
+fn classify(name: &str, prefix: &str) -> Option<Kind> {
+    let mentions = name.match_indices(prefix).map(|(index, _)| ident(&name[index + prefix.len()..])).collect::<Vec<_>>();
+    if mentions.is_empty() {
+        return None;
+    }
+    if mentions.iter().any(|item| HARNESS_ITEMS.contains(item)) || HARNESS_MODULES.iter().any(|module| name.contains(&format!("{}{}", CRATE, module))) {
+        return Some(Kind::Harness);
+    }
+    if let Some(path) = name.strip_prefix(prefix) {
+        let item = ident(path);
+        return Some(if item == "usage" {
+            Kind::Usage
+        } else if (path[item.len()..].starts_with("::") || path[item.len()..].starts_with('<')) && item.starts_with(char::is_uppercase) {
+            Kind::Builder
+        } else {
+            Kind::Other
+        });
+    }
+    if mentions.iter().any(|item| item.ends_with("Builder")) {
+        Some(Kind::Builder)
+    } else {
+        Some(Kind::Other)
+    }
+}
+
+/// Attribute function `symbols` to `targets`, sorted by contender then descending size
+pub fn attribute(symbols: &[Symbol], targets: &[(&'static str, String)]) -> Vec<Entry> {
+    let mut entries = vec![];
+    for symbol in symbols {
+        let name = elf::demangle(&symbol.name);
+        for (index, (contender, prefix)) in targets.iter().enumerate() {
+            if let Some(kind) = classify(&name, prefix) {
+                entries.push((index, Entry { contender, kind, symbol: name.replace(CRATE, ""), size: symbol.size }));
+            }
+        }
+    }
+    entries.sort_by(|(left, a), (right, b)| left.cmp(right).then(a.kind.cmp(&b.kind)).then(b.size.cmp(&a.size)).then(a.symbol.cmp(&b.symbol)));
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Functions of ELF file `bytes` attributed to `registry` contenders
+pub fn entries(bytes: &[u8], registry: &Registry) -> Result<Vec<Entry>, ElfError> {
+    Ok(attribute(&elf::symbols(bytes)?, &targets(registry)))
+}
+
+/// Code size per contender, in order of `entries`
+pub fn totals(entries: &[Entry]) -> Vec<Total> {
+    let mut totals: Vec<Total> = vec![];
+    for entry in entries {
+        if totals.last().map(|total| total.contender) != Some(entry.contender) {
+            totals.push(Total { contender: entry.contender, ..Total::default() });
+        }
+        let total = totals.last_mut().unwrap();
+        match entry.kind {
+            Kind::Builder => {
+                total.functions += 1;
+                total.builder += entry.size;
+            },
+            Kind::Usage => total.usage += entry.size,
+            Kind::Other => total.other += entry.size,
+            Kind::Harness => total.harness += entry.size,
+        }
+    }
+    totals
+}
+
+/// Render contender totals, then builder functions. CSV lists every attributed function.
+///
+/// Harness bytes are reported alongside totals, without being part of them.
+pub fn render(entries: &[Entry], format: Format) -> String {
+    let totals = totals(entries);
+    let builders = entries.iter().filter(|entry| entry.kind == Kind::Builder).collect::<Vec<_>>();
+    match format {
+        Format::Markdown => {
+            let mut markdown = String::from("| Contender | Builder functions | Builder bytes | Usage bytes | Other bytes | Total bytes | Harness bytes |\n| --- | --- | --- | --- | --- | --- | --- |\n");
+            for total in &totals {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} | {} | {} | {} |",
+                    total.contender, total.functions, total.builder, total.usage, total.other, total.total(), total.harness,
+                ).unwrap();
+            }
+            markdown.push_str("\n| Contender | Builder function | Bytes |\n| --- | --- | --- |\n");
+            for entry in builders {
+                writeln!(markdown, "| {} | `{}` | {} |", entry.contender, entry.symbol.replace('|', "\\|"), entry.size).unwrap();
+            }
+            markdown
+        },
+        Format::Json => {
+            let totals = totals
+                .iter()
+                .map(|total| format!(
+                    "{{\"contender\":{},\"functions\":{},\"builder\":{},\"usage\":{},\"other\":{},\"total\":{},\"harness\":{}}}",
+                    report::json_string(total.contender), total.functions, total.builder, total.usage, total.other, total.total(), total.harness,
+                ))
+                .collect::<Vec<_>>();
+            let symbols = entries
+                .iter()
+                .map(|entry| format!(
+                    "{{\"contender\":{},\"kind\":{},\"symbol\":{},\"size\":{}}}",
+                    report::json_string(entry.contender), report::json_string(entry.kind.label()), report::json_string(&entry.symbol), entry.size,
+                ))
+                .collect::<Vec<_>>();
+            format!("{{\"totals\":[{}],\"symbols\":[{}]}}", totals.join(","), symbols.join(","))
+        },
+        Format::Csv => {
+            let mut csv = String::from("contender,kind,symbol,size\n");
+            for entry in entries {
+                writeln!(csv, "{},{},{},{}", report::csv_field(entry.contender), entry.kind.label(), report::csv_field(&entry.symbol), entry.size).unwrap();
+            }
+            csv
+        },
+        Format::Text => {
+            let mut rows = vec![["Contender", "Builder functions", "Builder bytes", "Usage bytes", "Other bytes", "Total bytes", "Harness bytes"].map(str::to_owned).to_vec()];
+            for total in &totals {
+                rows.push(vec![
+                    total.contender.to_owned(),
+                    total.functions.to_string(),
+                    total.builder.to_string(),
+                    total.usage.to_string(),
+                    total.other.to_string(),
+                    total.total().to_string(),
+                    total.harness.to_string(),
+                ]);
+            }
+            let mut text = report::text_table(&rows, &[1]);
+            text.push('\n');
+            let mut rows = vec![["Contender", "Builder function", "Bytes"].map(str::to_owned).to_vec()];
+            for entry in builders {
+                rows.push(vec![entry.contender.to_owned(), entry.symbol.clone(), entry.size.to_string()]);
+            }
+            text.push_str(&report::text_table(&rows, &[1]));
+            text
+        },
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn symbol(name: &str, size: u64) -> Symbol {
+        Symbol { name: name.to_owned(), address: 0, size }
+    }
+
+    fn target(contender: &'static str, module: &str) -> (&'static str, String) {
+        (contender, format!("{}{}::", CRATE, module))
+    }
+
+    #[test]
+    pub fn symbols_are_attributed_by_module() {
+        let entries = attribute(&[
+            symbol("_ZN21rust_benchmark_setter9blueprint6setter4Root12push_mapitem17h0123456789abcdefE", 280),
+            symbol("_ZN4core3ptr85drop_in_place$LT$rust_benchmark_setter..typedbuilder..RootBuilder$LT$$LP$$RP$$GT$$GT$17h0123456789abcdefE", 40),
+            symbol("_ZN83_$LT$rust_benchmark_setter..derivebuilder..Root$u20$as$u20$core..cmp..PartialEq$GT$2eq17h0123456789abcdefE", 96),
+            symbol("_ZN21rust_benchmark_setter5bench7control5usage17h0123456789abcdefE", 512),
+            symbol("_ZN21rust_benchmark_setter5model4Root3new17h0123456789abcdefE", 64),
+            symbol("main", 16),
+        ], &[
+            target("literal", "bench::control"),
+            target("blueprint-setter", "blueprint::setter"),
+            target("blueprint-builder", "blueprint::builder"),
+            target("derive-builder", "derivebuilder"),
+            target("typed-builder", "typedbuilder"),
+        ]);
+
+        let attributed = entries.iter().map(|entry| (entry.contender, entry.kind)).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("literal", Kind::Usage),
+                ("blueprint-setter", Kind::Builder),
+                ("derive-builder", Kind::Other),
+                ("typed-builder", Kind::Builder),
+            ],
+            attributed,
+        );
+        assert_eq!("core::ptr::drop_in_place<typedbuilder::RootBuilder<()>>", entries[3].symbol);
+    }
+
+    #[test]
+    pub fn totals_sum_by_kind() {
+        let entry = |kind, size| Entry { contender: "derive-builder", kind, symbol: String::new(), size };
+        let totals = totals(&[entry(Kind::Builder, 100), entry(Kind::Builder, 20), entry(Kind::Usage, 30), entry(Kind::Other, 5), entry(Kind::Harness, 800)]);
+
+        assert_eq!(vec![Total { contender: "derive-builder", functions: 2, builder: 120, usage: 30, other: 5, harness: 800 }], totals);
+        assert_eq!(155, totals[0].total());
+    }
+
+    #[test]
+    pub fn harness_is_not_counted() {
+        let prefix = "rust_benchmark_setter::typedbuilder::";
+        for name in [
+            "<rust_benchmark_setter::typedbuilder::Demo as rust_benchmark_setter::conformance::Adapter>::apply",
+            "<rust_benchmark_setter::typedbuilder::Demo as rust_benchmark_setter::registry::Contender>::declaration",
+            "rust_benchmark_setter::typedbuilder::<impl rust_benchmark_setter::registry::Contender for rust_benchmark_setter::typedbuilder::Demo>::scaling::{{closure}}",
+            "<rust_benchmark_setter::model::Root as core::convert::From<rust_benchmark_setter::typedbuilder::Root>>::from",
+            "rust_benchmark_setter::typedbuilder::tests::usage_builds_every_field",
+        ] {
+            assert_eq!(Some(Kind::Harness), classify(name, prefix), "{}", name);
+        }
+        assert_eq!(Some(Kind::Builder), classify("rust_benchmark_setter::typedbuilder::RootBuilder<((u8,),)>::build", prefix));
+        assert_eq!(Some(Kind::Usage), classify("rust_benchmark_setter::typedbuilder::usage", prefix));
+
+        let entry = |kind, size| Entry { contender: "typed-builder", kind, symbol: String::new(), size };
+        let totals = totals(&[entry(Kind::Builder, 100), entry(Kind::Harness, 8309)]);
+        assert_eq!(100, totals[0].total());
+        assert!(render(&[entry(Kind::Builder, 100), entry(Kind::Harness, 8309)], Format::Csv).contains("typed-builder,harness,,8309\n"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn current_executable_has_builder_code() {
+        let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let registry = Registry::builtin();
+        let totals = totals(&entries(&bytes, &registry).unwrap());
+
+        for declaration in registry.declarations() {
+            let total = totals.iter().find(|total| total.contender == declaration.name).unwrap_or_else(|| panic!("No code for {}", declaration.name));
+            assert!(total.builder > 0 && total.usage > 0, "{:?}", total);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Conformance scenarios, shared by every contender.
+//!
+//! A scenario is a sequence of [`Op`]s and the [canonical](crate::model) root expected once applied.
+//! Contenders plug in by implementing [`Adapter`], applying operations through their own API.
+//! Results are collected into a pass/fail [`Grid`]:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::conformance::{Adapter,Grid};
+//! # use rust_benchmark_setter::report::Format;
+//! # use rust_benchmark_setter::{blueprint,derivebuilder,typedbuilder};
+//! let grid = Grid::run(&[&blueprint::setter::Demo, &derivebuilder::Demo, &typedbuilder::Demo]);
+//!
+//! assert!(grid.failures().is_empty());
+//! assert!(grid.render(Format::Markdown).contains("| Overwrite field | ✅ | ✅ | ➖ |"));
+//! ```
+//!
+//! Adapters translate each operation into contender API calls. When API can't express a sequence,
+//! such as `typed-builder` forbidding to call a setter twice, they report it [`Unsupported`] rather than working around it.
+
+use std::collections::BTreeMap;
+use std::fmt::{self,Write};
+use std::panic::{self,AssertUnwindSafe};
+use crate::model::{Item,Root};
+use crate::registry::Contender;
+use crate::report::{self,Format};
+
+/// Operation applied on a root under construction
+#[derive(Clone,Debug,PartialEq)]
+pub enum Op {
+    Number(u8),
+    Boolean(bool),
+    String(String),
+    OptString(String),
+    /// Set nested item, with given number
+    OptItem(u8),
+    /// Push a list item, with given number
+    ListItem(u8),
+    /// Insert a map item, with given key and number
+    MapItem(String, u8),
+}
+
+/// Operation sequence contender API can't express, with reason
+#[derive(Clone,Debug,PartialEq)]
+pub struct Unsupported(pub &'static str);
+
+/// Contender able to run conformance scenarios
+pub trait Adapter: Contender {
+    /// Build a root applying `ops` in order, or report contender API can't express them
+    ///
+    /// Panicking counts as a failure.
+    fn apply(&self, ops: &[Op]) -> Result<Root, Unsupported>;
+}
+
+/// Scenario result for a contender
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+    /// Scenario can't be expressed through contender API
+    Unsupported,
+}
+
+/// Conformance scenario
+#[derive(Clone,Debug,PartialEq)]
+pub struct Scenario {
+    pub name: &'static str,
+    pub ops: Vec<Op>,
+    pub expected: Root,
+}
+
+/// Pass/fail results of scenarios, per contender
+#[derive(Clone,Debug,PartialEq)]
+pub struct Grid {
+    pub contenders: Vec<&'static str>,
+    /// Scenario name, and result per contender
+    pub rows: Vec<(&'static str, Vec<Outcome>)>,
+}
+
+impl Op {
+    /// Apply on canonical root
+    pub fn apply(&self, root: &mut Root) {
+        match self {
+            Op::Number(number) => root.number = *number,
+            Op::Boolean(boolean) => root.boolean = *boolean,
+            Op::String(string) => root.string = string.clone(),
+            Op::OptString(string) => root.opt_string = Some(string.clone()),
+            Op::OptItem(number) => root.opt_item = Some(Item::number(*number)),
+            Op::ListItem(number) => root.listitems.push(Item::number(*number)),
+            Op::MapItem(key, number) => { root.mapitems.insert(key.clone(), Item::number(*number)); },
+        }
+    }
+}
+
+/// Expected canonical root, applying `ops` on default
+///
+/// ```rust
+/// # use rust_benchmark_setter::conformance::{self,Op};
+/// let root = conformance::fold(&[Op::Number(1), Op::Number(2)]);
+///
+/// assert_eq!(2, root.number);
+/// ```
+pub fn fold(ops: &[Op]) -> Root {
+    let mut root = Root::default();
+    for op in ops {
+        op.apply(&mut root);
+    }
+    root
+}
+
+/// Shared conformance scenarios
+pub fn scenarios() -> Vec<Scenario> {
+    let scenario = |name, ops: Vec<Op>, expected: Root| Scenario { name, ops, expected };
+    vec![
+        scenario("Set scalars", vec![Op::Number(1), Op::Boolean(true), Op::String("foo".to_owned())], Root {
+            number: 1,
+            boolean: true,
+            string: "foo".to_owned(),
+            ..Root::default()
+        }),
+        scenario("Strip option", vec![Op::OptString("bar".to_owned())], Root {
+            opt_string: Some("bar".to_owned()),
+            ..Root::default()
+        }),
+        scenario("Push list item", vec![Op::ListItem(3), Op::ListItem(5)], Root {
+            listitems: vec![Item::number(3), Item::number(5)],
+            ..Root::default()
+        }),
+        scenario("Insert map item", vec![Op::MapItem("foo".to_owned(), 4), Op::MapItem("bar".to_owned(), 6)], Root {
+            mapitems: BTreeMap::from([("foo".to_owned(), Item::number(4)), ("bar".to_owned(), Item::number(6))]),
+            ..Root::default()
+        }),
+        scenario("Nested item", vec![Op::OptItem(2)], Root {
+            opt_item: Some(Item::number(2)),
+            ..Root::default()
+        }),
+        scenario("Empty default", vec![], Root::default()),
+        scenario("Overwrite field", vec![Op::Number(1), Op::String("foo".to_owned()), Op::Number(2), Op::String("bar".to_owned())], Root {
+            number: 2,
+            string: "bar".to_owned(),
+            ..Root::default()
+        }),
+    ]
+}
+
+/// Whether `adapter` builds `scenario` expected root, or can't express it
+pub fn outcome(adapter: &dyn Adapter, scenario: &Scenario) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| adapter.apply(&scenario.ops))) {
+        Ok(Ok(root)) if root == scenario.expected => Outcome::Pass,
+        Ok(Err(Unsupported(_))) => Outcome::Unsupported,
+        _ => Outcome::Fail,
+    }
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported: {}", self.0)
+    }
+}
+
+impl Outcome {
+    fn emoji(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "✅",
+            Outcome::Fail => "❌",
+            Outcome::Unsupported => "➖",
+        }
+    }
+
+    fn text(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "pass",
+            Outcome::Fail => "FAIL",
+            Outcome::Unsupported => "n/a",
+        }
+    }
+
+    /// Whether passed, `None` when unsupported
+    pub fn passed(&self) -> Option<bool> {
+        match self {
+            Outcome::Pass => Some(true),
+            Outcome::Fail => Some(false),
+            Outcome::Unsupported => None,
+        }
+    }
+}
+
+impl Grid {
+    /// Run shared scenarios against `adapters`
+    pub fn run(adapters: &[&dyn Adapter]) -> Self {
+        Self {
+            contenders: adapters.iter().map(|adapter| adapter.declaration().name).collect(),
+            rows: scenarios()
+                .iter()
+                .map(|scenario| (scenario.name, adapters.iter().map(|adapter| outcome(*adapter, scenario)).collect()))
+                .collect(),
+        }
+    }
+
+    /// Failing scenario and contender names, unsupported ones excluded
+    pub fn failures(&self) -> Vec<(&'static str, &'static str)> {
+        self.rows
+            .iter()
+            .flat_map(|(scenario, results)| self.contenders
+                .iter()
+                .zip(results)
+                .filter(|(_, outcome)| **outcome == Outcome::Fail)
+                .map(move |(contender, _)| (*scenario, *contender))
+            )
+            .collect()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Json => self.to_json(),
+            Format::Csv => self.to_csv(),
+            Format::Text => self.to_text(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        writeln!(markdown, "| Scenario | {} |", self.contenders.join(" | ")).unwrap();
+        writeln!(markdown, "|---|{}", "---|".repeat(self.contenders.len())).unwrap();
+        for (scenario, results) in &self.rows {
+            let cells = results.iter().map(Outcome::emoji).collect::<Vec<_>>();
+            writeln!(markdown, "| {} | {} |", scenario, cells.join(" | ")).unwrap();
+        }
+        markdown
+    }
+
+    fn to_json(&self) -> String {
+        let rows = self.rows
+            .iter()
+            .map(|(scenario, results)| {
+                let results = self.contenders
+                    .iter()
+                    .zip(results)
+                    .map(|(contender, outcome)| match outcome.passed() {
+                        Some(passed) => format!("{}:{}", report::json_string(contender), passed),
+                        None => format!("{}:null", report::json_string(contender)),
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}:{{{}}}", report::json_string(scenario), results.join(","))
+            })
+            .collect::<Vec<_>>();
+        format!("{{{}}}", rows.join(","))
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("scenario,contender,passed\n");
+        for (scenario, results) in &self.rows {
+            for (contender, outcome) in self.contenders.iter().zip(results) {
+                let passed = outcome.passed().map(|passed| passed.to_string()).unwrap_or_default();
+                writeln!(csv, "{},{},{}", report::csv_field(scenario), report::csv_field(contender), passed).unwrap();
+            }
+        }
+        csv
+    }
+
+    fn to_text(&self) -> String {
+        let mut rows = vec![std::iter::once("Scenario").chain(self.contenders.iter().copied()).map(str::to_owned).collect::<Vec<_>>()];
+        for (scenario, results) in &self.rows {
+            rows.push(std::iter::once(scenario.to_string()).chain(results.iter().map(|outcome| outcome.text().to_owned())).collect());
+        }
+        report::text_table(&rows, &[1])
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::matrix::{Declaration,Feature,Support};
+    use crate::registry::Registry;
+
+    /// Declaration of adapters under test
+    pub static BROKEN: Declaration = Declaration {
+        name: "broken",
+        version: "0.1.0",
+        module: "broken",
+        claims: &[(Feature::ChainCall, Support::Supported)],
+        shapes: &[],
+    };
+
+    /// Downstream contender, folding operations as every conforming one
+    pub struct Folded;
+
+    pub static FOLDED: Declaration = Declaration {
+        name: "folded",
+        version: "0.1.0",
+        module: "folded",
+        claims: &[(Feature::ChainCall, Support::Supported)],
+        shapes: &[],
+    };
+
+    impl Contender for Folded {
+        fn declaration(&self) -> &Declaration {
+            &FOLDED
+        }
+
+        fn adapter(&self) -> Option<&dyn Adapter> {
+            Some(self)
+        }
+    }
+
+    impl Adapter for Folded {
+        fn apply(&self, ops: &[Op]) -> Result<Root, Unsupported> {
+            Ok(fold(ops))
+        }
+    }
+
+    #[test]
+    pub fn every_contender_conforms() {
+        let grid = Grid::run(&Registry::builtin().adapters());
+
+        assert_eq!(
+            Vec::<(&str, &str)>::new(),
+            grid.failures(),
+        );
+    }
+
+    #[test]
+    pub fn registered_adapters_are_run() {
+        let mut registry = Registry::builtin();
+        registry.register(Folded);
+        let grid = Grid::run(&registry.adapters());
+
+        assert_eq!(Some(&"folded"), grid.contenders.last());
+        assert!(grid.failures().is_empty(), "{:?}", grid.failures());
+    }
+
+    #[cfg(feature = "typed_builder")]
+    #[test]
+    pub fn unsupported_scenarios_are_reported() {
+        let grid = Grid::run(&[&crate::typedbuilder::Demo]);
+
+        assert_eq!(
+            vec![
+                ("Set scalars", Outcome::Pass),
+                ("Strip option", Outcome::Pass),
+                ("Push list item", Outcome::Unsupported),
+                ("Insert map item", Outcome::Unsupported),
+                ("Nested item", Outcome::Pass),
+                ("Empty default", Outcome::Pass),
+                ("Overwrite field", Outcome::Unsupported),
+            ],
+            grid.rows.iter().map(|(scenario, outcomes)| (*scenario, outcomes[0])).collect::<Vec<_>>(),
+        );
+        assert!(grid.failures().is_empty());
+        assert!(grid.render(Format::Json).contains(r#""Overwrite field":{"typed-builder":null}"#));
+        assert!(grid.render(Format::Csv).contains("Overwrite field,typed-builder,\n"));
+    }
+
+    #[test]
+    pub fn expected_roots_match_ops() {
+        for scenario in scenarios() {
+            assert_eq!(scenario.expected, fold(&scenario.ops), "{}", scenario.name);
+        }
+    }
+
+    #[test]
+    pub fn failing_adapter_is_reported() {
+        struct Broken;
+
+        impl Contender for Broken {
+            fn declaration(&self) -> &Declaration {
+                &BROKEN
+            }
+        }
+
+        impl Adapter for Broken {
+            fn apply(&self, ops: &[Op]) -> Result<Root, Unsupported> {
+                assert!(ops.len() < 4, "Too many ops");
+                Ok(Root::default())
+            }
+        }
+
+        let grid = Grid::run(&[&Broken]);
+
+        let failures = grid.failures().iter().map(|(scenario, _)| *scenario).collect::<Vec<_>>();
+        assert_eq!(vec!["Set scalars", "Strip option", "Push list item", "Insert map item", "Nested item", "Overwrite field"], failures);
+        assert!(grid.render(Format::Text).contains("Empty default    pass"));
+    }
+}
@@ -0,0 +1,422 @@
+//! Static HTML dashboard, published with documentation.
+//!
+//! Pages are self-contained: styles, scripts and charts are inlined, so they work offline.
+//! * `index.html`: interactive feature matrix, ranking and benchmark results
+//! * `contender-<module>.html`: contender details, with support per field shape
+//!
+//! ```rust
+//! # use rust_benchmark_setter::dashboard::Dashboard;
+//! # use rust_benchmark_setter::matrix::CONTENDERS;
+//! let pages = Dashboard::new(CONTENDERS).pages();
+//!
+//! assert_eq!("index.html", pages[0].0);
+//! assert_eq!("contender-derivebuilder.html", pages[1].0);
+//! ```
+
+use std::fmt::Write;
+use crate::matrix::{Declaration,Feature,Shape,Support};
+use crate::matrix::score::{Profile,Ranking};
+
+/// Benchmark result of a contender scenario
+#[derive(Clone,Debug,PartialEq)]
+pub struct Measurement {
+    pub contender: String,
+    pub scenario: String,
+    /// Nanoseconds per operation
+    pub nanos: f64,
+}
+
+/// Dashboard pages generator
+#[derive(Clone,Debug)]
+pub struct Dashboard<'a> {
+    contenders: Vec<&'a Declaration>,
+    profile: Profile,
+    measurements: Vec<Measurement>,
+}
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 70em; padding: 0 1em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: .3em .6em; text-align: left; }
+th { background: #f3f3f3; }
+td.support { text-align: center; }
+code { background: #f3f3f3; padding: 0 .2em; }
+fieldset { display: inline-block; vertical-align: top; margin: 0 1em 1em 0; }
+svg text { font-family: sans-serif; font-size: 12px; }
+.bar { fill: #4a7fb5; }
+";
+
+const SCRIPT: &str = "\
+document.querySelectorAll('input[data-toggle]').forEach(function (input) {
+  input.addEventListener('change', function () {
+    document.querySelectorAll(input.dataset.toggle).forEach(function (element) {
+      element.hidden = !input.checked;
+    });
+  });
+});
+document.getElementById('sort').addEventListener('click', function () {
+  var table = document.getElementById('matrix');
+  var headers = Array.from(table.querySelectorAll('thead th[data-contender]'));
+  headers.sort(function (a, b) { return b.dataset.score - a.dataset.score; });
+  var order = headers.map(function (header) { return header.dataset.contender; });
+  table.querySelectorAll('tr').forEach(function (row) {
+    order.forEach(function (contender) {
+      var cell = row.querySelector('[data-contender=\"' + contender + '\"]');
+      if (cell) { row.appendChild(cell); }
+    });
+  });
+});
+";
+
+impl Measurement {
+    /// Parse benchmark results from CSV, with `contender,scenario,ns_per_op` leading columns
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::dashboard::Measurement;
+    /// let measurements = Measurement::parse_csv("contender,scenario,ns_per_op\nfoo,usage,12.5\n").unwrap();
+    ///
+    /// assert_eq!(12.5, measurements[0].nanos);
+    /// ```
+    pub fn parse_csv(csv: &str) -> Result<Vec<Measurement>, String> {
+        csv.lines()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let fields = line.split(',').collect::<Vec<_>>();
+                match fields.as_slice() {
+                    [contender, scenario, nanos, ..] => Ok(Measurement {
+                        contender: contender.to_string(),
+                        scenario: scenario.to_string(),
+                        nanos: nanos.parse().map_err(|_| format!("line {}: invalid ns/op '{}'", index + 1, nanos))?,
+                    }),
+                    _ => Err(format!("line {}: expected 'contender,scenario,ns_per_op'", index + 1)),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a> Dashboard<'a> {
+    pub fn new(contenders: &[&'a Declaration]) -> Self {
+        Self {
+            contenders: contenders.to_vec(),
+            profile: Profile::uniform(),
+            measurements: vec![],
+        }
+    }
+
+    /// Rank contenders with `profile`
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Display benchmark results
+    pub fn with_measurements(mut self, measurements: Vec<Measurement>) -> Self {
+        self.measurements = measurements;
+        self
+    }
+
+    /// Generated pages, as `(file name, HTML)`
+    pub fn pages(&self) -> Vec<(String, String)> {
+        let mut pages = vec![("index.html".to_owned(), self.index())];
+        for contender in &self.contenders {
+            pages.push((page_name(contender), self.contender(contender)));
+        }
+        pages
+    }
+
+    fn index(&self) -> String {
+        let ranking = Ranking::new(&self.contenders, &self.profile);
+        let mut body = String::new();
+        writeln!(body, "<h1>Rust - Builder/Setter crate benchmarking</h1>").unwrap();
+        writeln!(body, "<p>See also <a href=\"rust_benchmark_setter/index.html\">API documentation</a>.</p>").unwrap();
+
+        writeln!(body, "<h2>Comparison</h2>").unwrap();
+        writeln!(body, "<form>").unwrap();
+        writeln!(body, "<fieldset><legend>Contenders</legend>").unwrap();
+        for contender in &self.contenders {
+            writeln!(
+                body,
+                "<label><input type=\"checkbox\" checked data-toggle=\"[data-contender='{0}']\"> {0}</label>",
+                escape(contender.name),
+            ).unwrap();
+        }
+        writeln!(body, "</fieldset>").unwrap();
+        writeln!(body, "<fieldset><legend>Features</legend>").unwrap();
+        for feature in Feature::ALL {
+            writeln!(
+                body,
+                "<label><input type=\"checkbox\" checked data-toggle=\"[data-feature='{}']\"> {}</label><br>",
+                feature.key(), label(feature.label()),
+            ).unwrap();
+        }
+        writeln!(body, "</fieldset>").unwrap();
+        writeln!(body, "<button type=\"button\" id=\"sort\">Sort by score</button>").unwrap();
+        writeln!(body, "</form>").unwrap();
+
+        writeln!(body, "<table id=\"matrix\">").unwrap();
+        write!(body, "<thead><tr><th>Feature</th>").unwrap();
+        for contender in &self.contenders {
+            write!(
+                body,
+                "<th data-contender=\"{}\" data-score=\"{}\"><a href=\"{}\">{} {}</a></th>",
+                escape(contender.name), ranking.score(contender), page_name(contender), escape(contender.name), escape(contender.version),
+            ).unwrap();
+        }
+        writeln!(body, "</tr></thead>").unwrap();
+        writeln!(body, "<tbody>").unwrap();
+        for feature in Feature::ALL {
+            write!(body, "<tr data-feature=\"{}\"><td>{}</td>", feature.key(), label(feature.label())).unwrap();
+            for contender in &self.contenders {
+                write!(body, "<td class=\"support\" data-contender=\"{}\">{}</td>", escape(contender.name), support_cell(contender.support(feature))).unwrap();
+            }
+            writeln!(body, "</tr>").unwrap();
+        }
+        write!(body, "<tr><th>Score</th>").unwrap();
+        for contender in &self.contenders {
+            write!(body, "<th data-contender=\"{}\">{}</th>", escape(contender.name), ranking.score(contender)).unwrap();
+        }
+        writeln!(body, "</tr>").unwrap();
+        writeln!(body, "</tbody></table>").unwrap();
+        body.push_str(&legend());
+
+        writeln!(body, "<h2>Ranking</h2>").unwrap();
+        let bars = ranking.scores().iter().map(|score| (score.contender.name.to_owned(), score.total() as f64)).collect::<Vec<_>>();
+        body.push_str(&bar_chart(&bars, "points"));
+        let drivers = ranking.drivers();
+        if !drivers.is_empty() {
+            writeln!(body, "<p>Ranking drivers:</p><ul>").unwrap();
+            for driver in drivers {
+                writeln!(body, "<li>{} (weight {}): {}</li>", label(driver.feature.label()), driver.weight, escape(&driver.summary())).unwrap();
+            }
+            writeln!(body, "</ul>").unwrap();
+        }
+
+        writeln!(body, "<h2>Benchmarks</h2>").unwrap();
+        body.push_str(&self.benchmarks(None));
+
+        page("Rust - Builder/Setter crate benchmarking", &body, true)
+    }
+
+    fn contender(&self, contender: &Declaration) -> String {
+        let mut body = String::new();
+        writeln!(body, "<p><a href=\"index.html\">← Dashboard</a></p>").unwrap();
+        writeln!(body, "<h1>{} {}</h1>", escape(contender.name), escape(contender.version)).unwrap();
+        writeln!(
+            body,
+            "<p><a href=\"https://crates.io/crates/{0}/{1}\">crates.io</a> · <a href=\"rust_benchmark_setter/{2}/index.html\">Demo module</a></p>",
+            escape(contender.name), escape(contender.version), escape(contender.module),
+        ).unwrap();
+
+        writeln!(body, "<h2>Features</h2>").unwrap();
+        writeln!(body, "<table><thead><tr><th>Feature</th><th>Support</th><th>Details</th></tr></thead><tbody>").unwrap();
+        for feature in Feature::ALL {
+            let support = contender.support(feature);
+            writeln!(
+                body,
+                "<tr><td>{}</td><td class=\"support\">{}</td><td><a href=\"rust_benchmark_setter/{}/index.html#{}\">{}</a></td></tr>",
+                label(feature.label()), support_cell(support), escape(contender.module), feature.anchor(),
+                support.map(|support| support.description()).unwrap_or("undeclared"),
+            ).unwrap();
+        }
+        writeln!(body, "</tbody></table>").unwrap();
+
+        writeln!(body, "<h2>Field shapes</h2>").unwrap();
+        write!(body, "<table><thead><tr><th>Feature</th>").unwrap();
+        for shape in Shape::ALL {
+            write!(body, "<th>{}</th>", label(shape.label())).unwrap();
+        }
+        writeln!(body, "</tr></thead><tbody>").unwrap();
+        for feature in Feature::ALL.into_iter().filter(|feature| !feature.shapes().is_empty()) {
+            write!(body, "<tr><td>{}</td>", label(feature.label())).unwrap();
+            for shape in Shape::ALL {
+                write!(body, "<td class=\"support\">{}</td>", contender.shape_support(feature, shape).map(|support| support_cell(Some(support))).unwrap_or_else(|| "-".to_owned())).unwrap();
+            }
+            writeln!(body, "</tr>").unwrap();
+        }
+        writeln!(body, "</tbody></table>").unwrap();
+        body.push_str(&legend());
+
+        writeln!(body, "<h2>Benchmarks</h2>").unwrap();
+        body.push_str(&self.benchmarks(Some(contender)));
+
+        page(&format!("{} {}", contender.name, contender.version), &body, false)
+    }
+
+    /// Benchmark table and chart per scenario, optionally for a single contender
+    fn benchmarks(&self, contender: Option<&Declaration>) -> String {
+        let measurements = self.measurements
+            .iter()
+            .filter(|measurement| contender.map(|contender| contender.name == measurement.contender).unwrap_or(true))
+            .collect::<Vec<_>>();
+        if measurements.is_empty() {
+            return "<p>No benchmark results.</p>\n".to_owned();
+        }
+        let mut scenarios = vec![];
+        for measurement in &measurements {
+            if !scenarios.contains(&measurement.scenario.as_str()) {
+                scenarios.push(measurement.scenario.as_str());
+            }
+        }
+        let mut html = String::new();
+        for scenario in scenarios {
+            let results = measurements.iter().filter(|measurement| measurement.scenario == scenario).collect::<Vec<_>>();
+            writeln!(html, "<h3>{}</h3>", escape(scenario)).unwrap();
+            writeln!(html, "<table><thead><tr><th>Contender</th><th>ns/op</th></tr></thead><tbody>").unwrap();
+            for result in &results {
+                writeln!(html, "<tr><td>{}</td><td>{:.1}</td></tr>", escape(&result.contender), result.nanos).unwrap();
+            }
+            writeln!(html, "</tbody></table>").unwrap();
+            let bars = results.iter().map(|result| (result.contender.clone(), result.nanos)).collect::<Vec<_>>();
+            html.push_str(&bar_chart(&bars, "ns/op"));
+        }
+        html
+    }
+}
+
+fn page_name(contender: &Declaration) -> String {
+    format!("contender-{}.html", contender.module.replace('/', "-"))
+}
+
+fn page(title: &str, body: &str, script: bool) -> String {
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>").unwrap();
+    writeln!(html, "<html lang=\"en\">").unwrap();
+    writeln!(html, "<head>").unwrap();
+    writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>{}</title>", escape(title)).unwrap();
+    writeln!(html, "<style>\n{}</style>", STYLE).unwrap();
+    writeln!(html, "</head>").unwrap();
+    writeln!(html, "<body>").unwrap();
+    html.push_str(body);
+    if script {
+        writeln!(html, "<script>\n{}</script>", SCRIPT).unwrap();
+    }
+    writeln!(html, "</body>").unwrap();
+    writeln!(html, "</html>").unwrap();
+    html
+}
+
+fn legend() -> String {
+    let mut html = String::from("<p>Legend:</p><ul>\n");
+    for support in Support::ALL {
+        writeln!(html, "<li>{}: {}</li>", support.emoji(), escape(support.description())).unwrap();
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn support_cell(support: Option<Support>) -> String {
+    support
+        .map(|support| format!("<span title=\"{}\">{}</span>", escape(support.description()), support.emoji()))
+        .unwrap_or_default()
+}
+
+/// Horizontal bar chart, as inline SVG
+///
+/// ```rust
+/// # use rust_benchmark_setter::dashboard::bar_chart;
+/// let svg = bar_chart(&[("foo".to_owned(), 2.0), ("bar".to_owned(), 1.0)], "points");
+///
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("<rect class=\"bar\" x=\"160\" y=\"4\" width=\"300\""));
+/// assert!(svg.contains("<rect class=\"bar\" x=\"160\" y=\"28\" width=\"150\""));
+/// ```
+pub fn bar_chart(bars: &[(String, f64)], unit: &str) -> String {
+    const LABEL: f64 = 160.0;
+    const WIDTH: f64 = 300.0;
+    const ROW: f64 = 24.0;
+    let max = bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    let height = ROW * bars.len() as f64;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\">",
+        LABEL + WIDTH + 120.0, height,
+    ).unwrap();
+    for (index, (name, value)) in bars.iter().enumerate() {
+        let y = ROW * index as f64;
+        let width = if max > 0.0 { WIDTH * value / max } else { 0.0 };
+        writeln!(svg, "<text x=\"0\" y=\"{}\">{}</text>", y + 16.0, escape(name)).unwrap();
+        writeln!(svg, "<rect class=\"bar\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>", LABEL, y + 4.0, width, ROW - 8.0).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\">{} {}</text>", LABEL + width + 6.0, y + 16.0, value, escape(unit)).unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escape HTML special characters
+pub fn escape(text: &str) -> String {
+    text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escape a Markdown label, rendering `` `code` `` spans
+fn label(text: &str) -> String {
+    escape(text)
+        .split('`')
+        .enumerate()
+        .map(|(index, part)| if index % 2 == 1 { format!("<code>{}</code>", part) } else { part.to_owned() })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::matrix::CONTENDERS;
+
+    #[test]
+    pub fn pages_are_self_contained() {
+        let dashboard = Dashboard::new(CONTENDERS)
+            .with_measurements(vec![
+                Measurement { contender: "derive-builder".to_owned(), scenario: "usage".to_owned(), nanos: 100.0 },
+            ]);
+        for (name, html) in dashboard.pages() {
+            for external in ["<script src", "<link", "@import", "url(", "@font-face"] {
+                assert!(!html.contains(external), "{} references external resource with {}", name, external);
+            }
+        }
+    }
+
+    #[test]
+    pub fn charts_bench_csv() {
+        let csv = "\
+contender,scenario,ns_per_op,iterations,allocations_per_op,bytes_per_op,samples,mad,min,max,ci_low,ci_high,vs_fastest,significant,overhead,overhead_significant
+derive-builder,usage,120.5,1000,6,240,10,1.5,118.0,125.0,119.0,122.0,1.21,true,20.5,true
+typed-builder,usage,99.5,1000,5,200,10,1.0,98.0,101.0,99.0,100.0,1.00,false,-0.5,false
+";
+        let measurements = Measurement::parse_csv(csv).expect("Invalid CSV");
+        let index = &Dashboard::new(CONTENDERS).with_measurements(measurements).pages()[0].1;
+
+        assert!(index.contains("<svg"), "{}", index);
+        assert!(index.contains("<tr><td>derive-builder</td><td>120.5</td></tr>"));
+        assert!(!index.contains("No benchmark results."));
+    }
+
+    #[test]
+    pub fn labels() {
+        assert_eq!("<code>Option&lt;T&gt;</code> field", label("`Option<T>` field"));
+        assert_eq!("Chain call", label("Chain call"));
+    }
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn benchmarks() {
+        let dashboard = Dashboard::new(CONTENDERS)
+            .with_measurements(vec![
+                Measurement { contender: "derive-builder".to_owned(), scenario: "usage".to_owned(), nanos: 100.0 },
+                Measurement { contender: "typed-builder".to_owned(), scenario: "usage".to_owned(), nanos: 50.0 },
+            ]);
+        let pages = dashboard.pages();
+
+        assert!(pages[0].1.contains("<tr><td>typed-builder</td><td>50.0</td></tr>"));
+        assert!(!pages[1].1.contains("<tr><td>typed-builder</td>"));
+        assert!(Dashboard::new(CONTENDERS).pages()[0].1.contains("No benchmark results."));
+    }
+}
@@ -14,17 +14,11 @@
 //! * No implemention of `Into` for builders
 //! * No support of `Into` for `HashMap` entries
 //!
-//! | Feature | [`derive-builder`](self) |
-//! | --- | --- |
-//! | [`fn builder()`](#feature---builder-function) | ✋ |
-//! | [`Into` field](#feature---into-field) | 🤏 |
-//! | [`Option` field](#feature---option-field) | 👍 |
-//! | [`Default` struct](#feature---default-struct) | 👍 |
-//! | [Collection field](#feature---collection-field) | ☝ |
-//! | [`Builder` field](#feature---builder-field) | ✋ |
-//! | [`Into` builder](#feature---into-builder) | ✋ |
-//! | [Chain call](#feature---chain-call) | 👍 |
-//! | [Builder customization](#feature---builder-customization) | 👍 |
+#![doc = include_str!("matrix/derivebuilder.md")]
+//!
+//! Support per field shape:
+//!
+#![doc = include_str!("matrix/derivebuilder.shapes.md")]
 //!
 //! ## Example
 //!
@@ -63,15 +57,24 @@
 //!     opt_string: Some("bar".to_owned()),
 //!     opt_item: Some(Item {
 //!         number: 2,
+//!         boolean: false,
+//!         string: "".to_owned(),
+//!         opt_string: None,
 //!     }),
 //!     listitems: vec![
 //!         Item {
 //!             number: 3,
+//!             boolean: false,
+//!             string: "".to_owned(),
+//!             opt_string: None,
 //!         }
 //!     ],
 //!     mapitems: HashMap::from([
 //!         ("foobar".to_owned(), Item {
 //!             number: 4,
+//!             boolean: false,
+//!             string: "".to_owned(),
+//!             opt_string: None,
 //!         })
 //!     ]),
 //! };
@@ -356,30 +359,70 @@
 //! # }
 //! ```
 //!
+//! ##### Feature - Builder reuse
+//!
+//! 👍
+//!
+//! `build()` borrows the builder and clones its fields, so it can be called repeatedly:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate derive_builder;
+//! #[derive(Builder,Debug,PartialEq)]
+//! struct Root {
+//!    foo: u8,
+//! }
+//! # pub fn main() {
+//! let mut builder = RootBuilder::default();
+//! builder.foo(1);
+//! let first = builder.build().expect("root error");
+//! let second = builder.build().expect("root error");
+//! assert_eq!(first, second);
+//! # }
+//! ```
+//!
 //!
 
 use std::collections::HashMap;
+use crate::matrix::{Declaration,Feature,Shape,Support};
+use crate::conformance::{Adapter,Op,Unsupported};
+use crate::model;
+use crate::registry::{Contender,Hook,ScalingHook};
 
-/// Root data structure
-#[derive(Builder,Clone,Debug,Default,PartialEq)]
-#[builder(default,setter(into,strip_option))]
-pub struct Root {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    pub opt_string: Option<String>,
-    pub opt_item: Option<Item>,
-    #[builder(setter(each(name="listitem",into)))]
-    pub listitems: Vec<Item>,
-    #[builder(setter(each(name="mapitem",into)))]
-    pub mapitems: HashMap<String, Item>,
-}
+/// Declared support levels
+pub const DECLARATION: Declaration = Declaration {
+    name: "derive-builder",
+    version: "0.12.0",
+    module: "derivebuilder",
+    claims: &[
+        (Feature::BuilderFunction, Support::CustomCode),
+        (Feature::IntoField, Support::Partial),
+        (Feature::OptionField, Support::Supported),
+        (Feature::DefaultStruct, Support::Supported),
+        (Feature::CollectionField, Support::FieldConfiguration),
+        (Feature::BuilderField, Support::CustomCode),
+        (Feature::IntoBuilder, Support::CustomCode),
+        (Feature::ChainCall, Support::Supported),
+        (Feature::BuilderCustomization, Support::Supported),
+        (Feature::BuilderReuse, Support::Supported),
+    ],
+    shapes: &[
+        (Feature::IntoField, Shape::Scalar, Support::Supported),
+        (Feature::IntoField, Shape::String, Support::Supported),
+        (Feature::IntoField, Shape::Option, Support::Supported),
+        (Feature::IntoField, Shape::OptionStruct, Support::Supported),
+        (Feature::IntoField, Shape::Vec, Support::Supported),
+        (Feature::IntoField, Shape::Nested, Support::Supported),
+    ],
+};
 
-/// Sub-item for Root data structure
-#[derive(Builder,Clone,Debug,Default,PartialEq)]
-#[builder(default,setter(into,strip_option))]
-pub struct Item {
-    pub number: u8,
+crate::benchmark_model! {
+    #[derive(Builder,Clone,Debug,Default,PartialEq)]
+    #[builder(default,setter(into,strip_option))]
+    map: HashMap,
+    option: [],
+    listitems: [#[builder(setter(each(name="listitem",into)))]],
+    mapitems: [#[builder(setter(each(name="mapitem",into)))]],
 }
 
 impl Root {
@@ -394,59 +437,209 @@ impl Item {
     }
 }
 
+/// Demo usage, building every field
+pub fn usage() -> Root {
+    Root::builder()
+        .number(1)
+        .boolean(true)
+        .string("foo")
+        .opt_string("bar")
+        .opt_item(Item::builder().number(2).build().expect("Unable to build opt_item"))
+        .listitem(Item::builder().number(3).build().expect("Unable to build listitem"))
+        .mapitem(("foobar".into(), Item::builder().number(4).build().expect("Unable to build mapitem")))
+        .build()
+        .expect("Unable to build root")
+}
+
+/// Registered [`Contender`]
+pub struct Demo;
+
+impl Contender for Demo {
+    fn declaration(&self) -> &Declaration {
+        &DECLARATION
+    }
+
+    fn scenarios(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || assert_eq!(
+                Root {
+                    number: 1,
+                    boolean: true,
+                    string: "foo".to_owned(),
+                    opt_string: Some("bar".to_owned()),
+                    opt_item: Some(Item { number: 2, boolean: false, string: "".to_owned(), opt_string: None }),
+                    listitems: vec![Item { number: 3, boolean: false, string: "".to_owned(), opt_string: None }],
+                    mapitems: HashMap::from([("foobar".to_owned(), Item { number: 4, boolean: false, string: "".to_owned(), opt_string: None })]),
+                },
+                usage(),
+            ) },
+            Hook { name: "default", run: || assert_eq!(
+                Root {
+                    number: 0,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                    opt_item: None,
+                    listitems: vec![],
+                    mapitems: HashMap::new(),
+                },
+                Root::builder().build().expect("Unable to build root"),
+            ) },
+        ]
+    }
+
+    fn benchmarks(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || { std::hint::black_box(usage()); } },
+        ]
+    }
+
+    fn scaling(&self) -> Vec<ScalingHook> {
+        vec![
+            ScalingHook { name: "listitems", run: |items| {
+                let mut builder = Root::builder();
+                for number in 0..items {
+                    builder.listitem(Item::builder().number(number as u8).build().expect("Unable to build listitem"));
+                }
+                std::hint::black_box(builder.build().expect("Unable to build root"));
+            } },
+            ScalingHook { name: "mapitems", run: |items| {
+                let mut builder = Root::builder();
+                for number in 0..items {
+                    builder.mapitem((number.to_string(), Item::builder().number(number as u8).build().expect("Unable to build mapitem")));
+                }
+                std::hint::black_box(builder.build().expect("Unable to build root"));
+            } },
+        ]
+    }
+
+    fn adapter(&self) -> Option<&dyn Adapter> {
+        Some(self)
+    }
+}
+
+impl Adapter for Demo {
+    fn apply(&self, ops: &[Op]) -> Result<model::Root, Unsupported> {
+        let item = |number: u8| Item::builder().number(number).build().expect("Unable to build item");
+        let mut builder = Root::builder();
+        for op in ops {
+            match op {
+                Op::Number(number) => builder.number(*number),
+                Op::Boolean(boolean) => builder.boolean(*boolean),
+                Op::String(string) => builder.string(string.as_str()),
+                Op::OptString(string) => builder.opt_string(string.as_str()),
+                Op::OptItem(number) => builder.opt_item(item(*number)),
+                Op::ListItem(number) => builder.listitem(item(*number)),
+                Op::MapItem(key, number) => builder.mapitem((key.clone(), item(*number))),
+            };
+        }
+        Ok(builder.build().expect("Unable to build root").into())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::evidence;
+    use crate::matrix::Feature;
 
-    #[test]
-    pub fn usage() {
-        let actual = Root::builder()
-                .number(1)
-                .boolean(true)
-                .string("foo")
-                .opt_string("bar")
-                .opt_item(Item::builder().number(2).build().expect("Unable to build opt_item"))
-                .listitem(Item::builder().number(3).build().expect("Unable to build listitem"))
-                .mapitem(("foobar".into(), Item::builder().number(4).build().expect("Unable to build mapitem")))
-                .build().expect("Unable to build root");
+    impl RootBuilder {
+        pub fn foobar(&mut self, foo: &str, bar: &str) -> &mut Self {
+            self
+                .string(foo)
+                .opt_string(bar)
+        }
+    }
 
-        let expected = Root {
-            number: 1,
-            boolean: true,
-            string: "foo".to_owned(),
-            opt_string: Some("bar".to_owned()),
-            opt_item: Some(Item {
-                number: 2,
-            }),
-            listitems: vec![
-                Item {
-                    number: 3,
-                }
-            ],
-            mapitems: HashMap::from([
-                ("foobar".to_owned(), Item {
-                    number: 4,
-                })
-            ]),
-        };
+    evidence! {
+        #[evidence(Feature::IntoField, Feature::OptionField, Feature::CollectionField, Feature::ChainCall)]
+        pub fn usage() {
+            let actual = Root::builder()
+                    .number(1)
+                    .boolean(true)
+                    .string("foo")
+                    .opt_string("bar")
+                    .opt_item(Item::builder().number(2).build().expect("Unable to build opt_item"))
+                    .listitem(Item::builder().number(3).build().expect("Unable to build listitem"))
+                    .mapitem(("foobar".into(), Item::builder().number(4).build().expect("Unable to build mapitem")))
+                    .build().expect("Unable to build root");
 
-        assert_eq!(expected, actual);
-    }
+            let expected = Root {
+                number: 1,
+                boolean: true,
+                string: "foo".to_owned(),
+                opt_string: Some("bar".to_owned()),
+                opt_item: Some(Item {
+                    number: 2,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                }),
+                listitems: vec![
+                    Item {
+                        number: 3,
+                        boolean: false,
+                        string: "".to_owned(),
+                        opt_string: None,
+                    }
+                ],
+                mapitems: HashMap::from([
+                    ("foobar".to_owned(), Item {
+                        number: 4,
+                        boolean: false,
+                        string: "".to_owned(),
+                        opt_string: None,
+                    })
+                ]),
+            };
+
+            assert_eq!(expected, actual);
+        }
+
+        #[evidence(Feature::DefaultStruct)]
+        pub fn support_default() {
+            let actual = Root::builder().build().expect("Failed to default");
 
-    #[test]
-    pub fn support_default() {
-        let actual = Root::builder().build().expect("Failed to default");
+            let expected = Root {
+                number: 0,
+                boolean: false,
+                string: "".to_owned(),
+                opt_string: None,
+                opt_item: None,
+                listitems: vec![],
+                mapitems: HashMap::from([]),
+            };
+
+            assert_eq!(expected, actual);
+        }
+
+        #[evidence(Feature::BuilderCustomization)]
+        pub fn customization() {
+            let actual = Root::builder()
+                .foobar("foo", "bar")
+                .build()
+                .expect("Unable to build root");
+
+            assert_eq!("foo", actual.string);
+            assert_eq!(Some("bar"), actual.opt_string.as_deref());
+        }
+
+        #[evidence(Feature::BuilderReuse)]
+        pub fn repeated_build() {
+            let mut builder = Root::builder();
+            builder
+                .number(1)
+                .listitem(Item::builder().number(2).build().expect("Unable to build listitem"));
 
-        let expected = Root {
-            number: 0,
-            boolean: false,
-            string: "".to_owned(),
-            opt_string: None,
-            opt_item: None,
-            listitems: vec![],
-            mapitems: HashMap::from([]),
-        };
+            let first = builder.build().expect("Unable to build first root");
+            let second = builder.build().expect("Unable to build second root");
+            assert_eq!(first, second);
+            assert_eq!(1, second.listitems.len());
 
-        assert_eq!(expected, actual);
+            builder.number(3);
+            let third = builder.build().expect("Unable to build third root");
+            assert_eq!(3, third.number);
+            assert_eq!(first.listitems, third.listitems);
+        }
     }
 }
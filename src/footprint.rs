@@ -0,0 +1,347 @@
+//! Memory footprint of builder types, next to their target struct.
+//!
+//! Entries are grouped into sections, printed by `report --footprint`:
+//! * `types`: builders of every contender, setters mutating their target in place
+//! * `typestates`: `typed-builder` root builder after each setter call, as set fields are encoded into its generic tuple
+//! * `growth`: builders of generated structs holding 1, 2, 4 and 8 `u64` fields
+//!
+//! ```rust
+//! # use rust_benchmark_setter::footprint;
+//! let entries = footprint::growth();
+//! let derive = entries.iter().filter(|entry| entry.contender == "derive-builder").collect::<Vec<_>>();
+//!
+//! // Each field is wrapped into an `Option`
+//! assert_eq!("Fields8Builder", derive[3].builder.type_name);
+//! assert_eq!(8 * 8, derive[3].overhead());
+//! ```
+
+use std::any;
+#[cfg(feature = "typed_builder")]
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::mem;
+use crate::report::{self,Format};
+#[cfg(feature = "blueprint")]
+use crate::blueprint;
+#[cfg(feature = "derive_builder")]
+use crate::derivebuilder;
+#[cfg(feature = "typed_builder")]
+use crate::typedbuilder;
+
+/// Size and alignment of a type
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Footprint {
+    /// Type name, without module paths
+    pub type_name: String,
+    pub size: usize,
+    pub align: usize,
+}
+
+/// Builder footprint, next to its target struct
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Entry {
+    pub section: &'static str,
+    pub contender: &'static str,
+    pub target: Footprint,
+    pub builder: Footprint,
+}
+
+impl Footprint {
+    pub fn of<T>() -> Self {
+        Self {
+            type_name: short_name(any::type_name::<T>()),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+        }
+    }
+
+    /// Footprint of `value` type, for types which can't be named, such as typestates
+    pub fn of_val<T>(_: &T) -> Self {
+        Self::of::<T>()
+    }
+}
+
+impl Entry {
+    /// Builder size minus target size, in bytes
+    pub fn overhead(&self) -> isize {
+        self.builder.size as isize - self.target.size as isize
+    }
+}
+
+/// Strip module paths from `type_name`
+///
+/// ```rust
+/// # use rust_benchmark_setter::footprint;
+/// assert_eq!("Option<Vec<Item>>", footprint::short_name("core::option::Option<alloc::vec::Vec<foo::Item>>"));
+/// assert_eq!("RootBuilder<((u8,), ())>", footprint::short_name("foo::RootBuilder<((u8,), ())>"));
+/// ```
+pub fn short_name(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut path = String::new();
+    for c in type_name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+            continue;
+        }
+        short.push_str(path.rsplit("::").next().unwrap_or_default());
+        path.clear();
+        short.push(c);
+    }
+    short.push_str(path.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+/// Builders of every enabled contender, for `Root` and `Item`
+///
+/// ```rust
+/// # use rust_benchmark_setter::footprint;
+/// let entries = footprint::types();
+/// let setter = entries.iter().find(|entry| entry.contender == "blueprint-setter").unwrap();
+///
+/// assert_eq!(0, setter.overhead());
+/// ```
+pub fn types() -> Vec<Entry> {
+    #[allow(unused_variables)]
+    let entry = |contender, target, builder| Entry { section: "types", contender, target, builder };
+    vec![
+        #[cfg(feature = "blueprint")]
+        entry("blueprint-setter", Footprint::of::<blueprint::setter::Root>(), Footprint::of::<blueprint::setter::Root>()),
+        #[cfg(feature = "blueprint")]
+        entry("blueprint-setter", Footprint::of::<blueprint::setter::Item>(), Footprint::of::<blueprint::setter::Item>()),
+        #[cfg(feature = "blueprint")]
+        entry("blueprint-builder", Footprint::of::<blueprint::builder::Root>(), Footprint::of::<blueprint::builder::RootBuilder>()),
+        #[cfg(feature = "blueprint")]
+        entry("blueprint-builder", Footprint::of::<blueprint::builder::Item>(), Footprint::of::<blueprint::builder::ItemBuilder>()),
+        #[cfg(feature = "derive_builder")]
+        entry("derive-builder", Footprint::of::<derivebuilder::Root>(), Footprint::of::<derivebuilder::RootBuilder>()),
+        #[cfg(feature = "derive_builder")]
+        entry("derive-builder", Footprint::of::<derivebuilder::Item>(), Footprint::of::<derivebuilder::ItemBuilder>()),
+        #[cfg(feature = "typed_builder")]
+        entry("typed-builder", Footprint::of::<typedbuilder::Root>(), Footprint::of_val(&typedbuilder::Root::builder())),
+        #[cfg(feature = "typed_builder")]
+        entry("typed-builder", Footprint::of::<typedbuilder::Item>(), Footprint::of_val(&typedbuilder::Item::builder())),
+    ]
+}
+
+/// `typed-builder` root builder after each setter call, in `usage` order
+///
+/// ```rust
+/// # use rust_benchmark_setter::footprint;
+/// let entries = footprint::typestates();
+///
+/// assert_eq!(8, entries.len());
+/// assert_eq!(0, entries[0].builder.size);
+/// ```
+#[cfg(feature = "typed_builder")]
+pub fn typestates() -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut push = |builder| entries.push(Entry {
+        section: "typestates",
+        contender: "typed-builder",
+        target: Footprint::of::<typedbuilder::Root>(),
+        builder,
+    });
+    let builder = typedbuilder::Root::builder();
+    push(Footprint::of_val(&builder));
+    let builder = builder.number(1);
+    push(Footprint::of_val(&builder));
+    let builder = builder.boolean(true);
+    push(Footprint::of_val(&builder));
+    let builder = builder.string("foo");
+    push(Footprint::of_val(&builder));
+    let builder = builder.opt_string("bar");
+    push(Footprint::of_val(&builder));
+    let builder = builder.opt_item(typedbuilder::Item::builder().build());
+    push(Footprint::of_val(&builder));
+    let builder = builder.listitems(vec![]);
+    push(Footprint::of_val(&builder));
+    let builder = builder.mapitems(HashMap::new());
+    push(Footprint::of_val(&builder));
+    entries
+}
+
+/// Declare structs of growing field count, with a builder per contender
+macro_rules! growth {
+    ($($name:ident => $builder:ident { $($field:ident),* })*) => {
+        // Only measured, never built
+        #[cfg(feature = "derive_builder")]
+        #[allow(dead_code)]
+        mod derive {
+            $(
+                #[derive(Builder)]
+                pub struct $name {
+                    $(pub $field: u64,)*
+                }
+            )*
+        }
+
+        #[cfg(feature = "typed_builder")]
+        #[allow(dead_code)]
+        mod typed {
+            use typed_builder::TypedBuilder;
+            $(
+                #[derive(TypedBuilder)]
+                pub struct $name {
+                    $(pub $field: u64,)*
+                }
+            )*
+        }
+
+        /// Builders of structs holding 1, 2, 4 and 8 `u64` fields
+        ///
+        /// `typed-builder` builders are reported empty (`typed-builder`), then with every field set (`typed-builder (set)`).
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        pub fn growth() -> Vec<Entry> {
+            let mut entries = vec![];
+            $(
+                #[cfg(feature = "derive_builder")]
+                entries.push(Entry { section: "growth", contender: "derive-builder", target: Footprint::of::<derive::$name>(), builder: Footprint::of::<derive::$builder>() });
+                #[cfg(feature = "typed_builder")]
+                {
+                    let target = Footprint::of::<typed::$name>();
+                    let builder = typed::$name::builder();
+                    entries.push(Entry { section: "growth", contender: "typed-builder", target: target.clone(), builder: Footprint::of_val(&builder) });
+                    let builder = builder$(.$field(1))*;
+                    entries.push(Entry { section: "growth", contender: "typed-builder (set)", target, builder: Footprint::of_val(&builder) });
+                }
+            )*
+            entries
+        }
+    };
+}
+
+growth! {
+    Fields1 => Fields1Builder { a }
+    Fields2 => Fields2Builder { a, b }
+    Fields4 => Fields4Builder { a, b, c, d }
+    Fields8 => Fields8Builder { a, b, c, d, e, f, g, h }
+}
+
+/// Every section
+pub fn entries() -> Vec<Entry> {
+    #[allow(unused_mut)]
+    let mut entries = types();
+    #[cfg(feature = "typed_builder")]
+    entries.extend(typestates());
+    entries.extend(growth());
+    entries
+}
+
+/// Render `entries` in given `format`
+///
+/// ```rust
+/// # use rust_benchmark_setter::footprint;
+/// # use rust_benchmark_setter::report::Format;
+/// let csv = footprint::render(&footprint::types(), Format::Csv);
+///
+/// assert!(csv.starts_with("section,contender,target,target_size,target_align,builder,builder_size,builder_align,overhead\n"));
+/// assert!(csv.contains("\ntypes,derive-builder,Item,"));
+/// ```
+pub fn render(entries: &[Entry], format: Format) -> String {
+    match format {
+        Format::Markdown => {
+            let mut markdown = String::from("| Section | Contender | Target | Size | Align | Builder | Size | Align | Overhead |\n| --- | --- | --- | --- | --- | --- | --- | --- | --- |\n");
+            for entry in entries {
+                writeln!(
+                    markdown,
+                    "| {} | {} | `{}` | {} | {} | `{}` | {} | {} | {:+} |",
+                    entry.section, entry.contender,
+                    entry.target.type_name, entry.target.size, entry.target.align,
+                    entry.builder.type_name, entry.builder.size, entry.builder.align,
+                    entry.overhead(),
+                ).unwrap();
+            }
+            markdown
+        },
+        Format::Json => {
+            let footprint = |footprint: &Footprint| format!(
+                "{{\"type\":{},\"size\":{},\"align\":{}}}",
+                report::json_string(&footprint.type_name), footprint.size, footprint.align,
+            );
+            let entries = entries
+                .iter()
+                .map(|entry| format!(
+                    "{{\"section\":{},\"contender\":{},\"target\":{},\"builder\":{},\"overhead\":{}}}",
+                    report::json_string(entry.section), report::json_string(entry.contender),
+                    footprint(&entry.target), footprint(&entry.builder), entry.overhead(),
+                ))
+                .collect::<Vec<_>>();
+            format!("[{}]", entries.join(","))
+        },
+        Format::Csv => {
+            let mut csv = String::from("section,contender,target,target_size,target_align,builder,builder_size,builder_align,overhead\n");
+            for entry in entries {
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{}",
+                    entry.section, report::csv_field(entry.contender),
+                    report::csv_field(&entry.target.type_name), entry.target.size, entry.target.align,
+                    report::csv_field(&entry.builder.type_name), entry.builder.size, entry.builder.align,
+                    entry.overhead(),
+                ).unwrap();
+            }
+            csv
+        },
+        Format::Text => {
+            let mut rows = vec![["Section", "Contender", "Target", "Size", "Align", "Builder", "Size", "Align", "Overhead"].map(str::to_owned).to_vec()];
+            for entry in entries {
+                rows.push(vec![
+                    entry.section.to_owned(),
+                    entry.contender.to_owned(),
+                    entry.target.type_name.clone(),
+                    entry.target.size.to_string(),
+                    entry.target.align.to_string(),
+                    entry.builder.type_name.clone(),
+                    entry.builder.size.to_string(),
+                    entry.builder.align.to_string(),
+                    format!("{:+}", entry.overhead()),
+                ]);
+            }
+            // Header, then a rule ahead of each section
+            let separators = (0..entries.len())
+                .filter(|index| *index == 0 || entries[index - 1].section != entries[*index].section)
+                .map(|index| index + 1)
+                .collect::<Vec<_>>();
+            report::text_table(&rows, &separators)
+        },
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[cfg(feature = "typed_builder")]
+    #[test]
+    pub fn typestates_grow_with_set_fields() {
+        let sizes = typestates().iter().map(|entry| entry.builder.size).collect::<Vec<_>>();
+
+        assert!(sizes.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", sizes);
+        assert!(*sizes.last().unwrap() >= mem::size_of::<typedbuilder::Root>());
+    }
+
+    #[test]
+    pub fn growth_overhead_grows_with_fields() {
+        let contenders: &[&str] = &[
+            #[cfg(feature = "derive_builder")]
+            "derive-builder",
+            #[cfg(feature = "typed_builder")]
+            "typed-builder",
+            #[cfg(feature = "typed_builder")]
+            "typed-builder (set)",
+        ];
+        for &contender in contenders {
+            let overheads = growth().iter().filter(|entry| entry.contender == contender).map(Entry::overhead).collect::<Vec<_>>();
+
+            assert_eq!(4, overheads.len(), "{}", contender);
+            assert!(overheads.windows(2).all(|pair| pair[0].abs() <= pair[1].abs()), "{}: {:?}", contender, overheads);
+        }
+    }
+
+    #[test]
+    pub fn derive_builders_hold_every_field() {
+        for entry in entries().iter().filter(|entry| entry.contender == "derive-builder") {
+            assert!(entry.overhead() >= 0, "{:?}", entry);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Differential fuzzing of [conformance](crate::conformance) operation sequences.
+//!
+//! Random sequences are applied to every contender, and compared with `blueprint::setter` as oracle.
+//! Each contender is only fuzzed over sequences its API can express, others being [unsupported](crate::conformance::Unsupported):
+//! `typed-builder` never repeats a setter nor adds collection items.
+//! Generation is seeded, so a failing sequence is reproduced from its seed, then shrunk to a minimal one:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::conformance::Adapter;
+//! # use rust_benchmark_setter::fuzz;
+//! # use rust_benchmark_setter::{blueprint,derivebuilder,typedbuilder};
+//! let adapters: [&dyn Adapter; 2] = [&derivebuilder::Demo, &typedbuilder::Demo];
+//! let failures = fuzz::run(&blueprint::setter::Demo, &adapters, 42, 100);
+//!
+//! assert!(failures.is_empty(), "{}", failures[0]);
+//! ```
+//!
+//! Tests run a fixed seed, override it with `FUZZ_SEED` and `FUZZ_CASES` environment variables (see `just fuzz`).
+
+use std::fmt;
+use std::panic::{self,AssertUnwindSafe};
+use crate::conformance::{Adapter,Op,Unsupported};
+use crate::model::Root;
+use crate::script;
+
+/// Longest generated sequence
+pub const MAX_LEN: usize = 12;
+
+/// Seeds tried per fuzzed case, before giving up on a contender unable to express sequences
+pub const ATTEMPTS: u64 = 100;
+
+/// Few keys, so that map items collide
+const KEYS: &[&str] = &["foo", "bar", "foobar"];
+
+const STRINGS: &[&str] = &["", "foo", "bar"];
+
+/// Seedable pseudo-random generator (SplitMix64)
+#[derive(Clone,Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+/// Sequence diverging from the oracle
+#[derive(Clone,Debug,PartialEq)]
+pub struct Failure {
+    pub contender: &'static str,
+    /// Seed generating the original sequence
+    pub seed: u64,
+    /// Shrunk sequence
+    pub ops: Vec<Op>,
+    pub expected: Option<Result<Root, Unsupported>>,
+    /// `None` when contender panicked
+    pub actual: Option<Result<Root, Unsupported>>,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Value in `0..bound`
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::fuzz::Rng;
+    /// let mut rng = Rng::new(1);
+    ///
+    /// assert!((0..100).all(|_| rng.below(3) < 3));
+    /// ```
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    /// Random operation
+    pub fn op(&mut self) -> Op {
+        let number = self.below(256) as u8;
+        match self.below(7) {
+            0 => Op::Number(number),
+            1 => Op::Boolean(self.below(2) == 1),
+            2 => Op::String(self.pick(STRINGS).to_string()),
+            3 => Op::OptString(self.pick(STRINGS).to_string()),
+            4 => Op::OptItem(number),
+            5 => Op::ListItem(number),
+            _ => Op::MapItem(self.pick(KEYS).to_string(), number),
+        }
+    }
+
+    /// Random sequence, up to `max_len` operations
+    pub fn sequence(&mut self, max_len: usize) -> Vec<Op> {
+        let len = self.below(max_len as u64 + 1) as usize;
+        (0..len).map(|_| self.op()).collect()
+    }
+}
+
+/// Build `ops` through `adapter`, `None` when it panics
+pub fn apply(adapter: &dyn Adapter, ops: &[Op]) -> Option<Result<Root, Unsupported>> {
+    panic::catch_unwind(AssertUnwindSafe(|| adapter.apply(ops))).ok()
+}
+
+/// Whether `adapter` builds something else than `oracle`, unless it can't express `ops`
+pub fn diverges(oracle: &dyn Adapter, adapter: &dyn Adapter, ops: &[Op]) -> bool {
+    match apply(adapter, ops) {
+        Some(Err(_)) => false,
+        actual => apply(oracle, ops) != actual,
+    }
+}
+
+/// Simpler variants of `op`
+fn simplify(op: &Op) -> Vec<Op> {
+    let numbers = |number: u8| [0, number / 2].into_iter().filter(move |simpler| *simpler != number);
+    match op {
+        Op::Number(number) => numbers(*number).map(Op::Number).collect(),
+        Op::OptItem(number) => numbers(*number).map(Op::OptItem).collect(),
+        Op::ListItem(number) => numbers(*number).map(Op::ListItem).collect(),
+        Op::MapItem(key, number) => numbers(*number).map(|number| Op::MapItem(key.clone(), number)).collect(),
+        Op::Boolean(true) => vec![Op::Boolean(false)],
+        Op::String(string) if !string.is_empty() => vec![Op::String(String::new())],
+        Op::OptString(string) if !string.is_empty() => vec![Op::OptString(String::new())],
+        _ => vec![],
+    }
+}
+
+/// Shrink `ops` while it keeps `failing`, removing then simplifying operations
+///
+/// ```rust
+/// # use rust_benchmark_setter::conformance::Op;
+/// # use rust_benchmark_setter::fuzz;
+/// let ops = vec![Op::Number(7), Op::ListItem(200), Op::Boolean(true)];
+///
+/// assert_eq!(vec![Op::ListItem(0)], fuzz::shrink(ops, |ops| ops.iter().any(|op| matches!(op, Op::ListItem(_)))));
+/// ```
+pub fn shrink<F: Fn(&[Op]) -> bool>(mut ops: Vec<Op>, failing: F) -> Vec<Op> {
+    loop {
+        let removed = (0..ops.len())
+            .map(|index| {
+                let mut candidate = ops.clone();
+                candidate.remove(index);
+                candidate
+            })
+            .find(|candidate| failing(candidate));
+        if let Some(candidate) = removed {
+            ops = candidate;
+            continue;
+        }
+        let simplified = (0..ops.len())
+            .flat_map(|index| simplify(&ops[index]).into_iter().map(move |op| (index, op)))
+            .map(|(index, op)| {
+                let mut candidate = ops.clone();
+                candidate[index] = op;
+                candidate
+            })
+            .find(|candidate| failing(candidate));
+        match simplified {
+            Some(candidate) => ops = candidate,
+            None => return ops,
+        }
+    }
+}
+
+/// Whether `adapter` API can express `ops`
+pub fn supports(adapter: &dyn Adapter, ops: &[Op]) -> bool {
+    !matches!(apply(adapter, ops), Some(Err(_)))
+}
+
+/// Fuzz `adapters` against `oracle`, with `cases` sequences each can express, seeded from `seed` onwards
+///
+/// Reports first failure of each contender, shrunk.
+pub fn run(oracle: &dyn Adapter, adapters: &[&dyn Adapter], seed: u64, cases: u64) -> Vec<Failure> {
+    adapters
+        .iter()
+        .filter_map(|adapter| (seed..seed.saturating_add(cases.saturating_mul(ATTEMPTS)))
+            .map(|seed| (seed, Rng::new(seed).sequence(MAX_LEN)))
+            .filter(|(_, ops)| supports(*adapter, ops))
+            .take(cases as usize)
+            .find_map(|(seed, ops)| {
+                if !diverges(oracle, *adapter, &ops) {
+                    return None;
+                }
+                let ops = shrink(ops, |ops| diverges(oracle, *adapter, ops));
+                Some(Failure {
+                    contender: adapter.declaration().name,
+                    seed,
+                    expected: apply(oracle, &ops),
+                    actual: apply(*adapter, &ops),
+                    ops,
+                })
+            })
+        )
+        .collect()
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} diverges from oracle (seed {}) on `{}`", self.contender, self.seed, script::render(&self.ops))?;
+        writeln!(f, "  expected: {:?}", self.expected)?;
+        write!(f, "  actual:   {:?}", self.actual)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::conformance::{self,tests::{BROKEN,Folded}};
+    use crate::matrix::Declaration;
+    use crate::registry::Contender;
+
+    #[cfg(feature = "blueprint")]
+    fn env(name: &str, default: u64) -> u64 {
+        std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+    }
+
+    #[cfg(feature = "blueprint")]
+    #[test]
+    pub fn contenders_match_oracle() {
+        let registry = crate::registry::Registry::builtin();
+        let failures = run(&crate::blueprint::setter::Demo, &registry.adapters(), env("FUZZ_SEED", 0), env("FUZZ_CASES", 500));
+
+        assert!(failures.is_empty(), "{}", failures.iter().map(|failure| failure.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    pub fn generation_is_seeded() {
+        assert_eq!(Rng::new(7).sequence(MAX_LEN), Rng::new(7).sequence(MAX_LEN));
+        assert_ne!(
+            (0..10).map(|seed| Rng::new(seed).sequence(MAX_LEN)).collect::<Vec<_>>(),
+            (10..20).map(|seed| Rng::new(seed).sequence(MAX_LEN)).collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    pub fn failures_are_shrunk() {
+        /// Keeps first map item inserted per key
+        struct FirstWins;
+
+        impl Contender for FirstWins {
+            fn declaration(&self) -> &Declaration {
+                &BROKEN
+            }
+        }
+
+        impl Adapter for FirstWins {
+            fn apply(&self, ops: &[Op]) -> Result<Root, Unsupported> {
+                let mut root = Root::default();
+                for op in ops {
+                    match op {
+                        Op::MapItem(key, _) if root.mapitems.contains_key(key) => {},
+                        op => op.apply(&mut root),
+                    }
+                }
+                Ok(root)
+            }
+        }
+
+        let failures = run(&Folded, &[&FirstWins], 0, 500);
+
+        assert_eq!(1, failures.len());
+        let failure = &failures[0];
+        assert_eq!(2, failure.ops.len(), "{}", failure);
+        assert!(matches!(&failure.ops[..], [Op::MapItem(first, _), Op::MapItem(second, _)] if first == second), "{}", failure);
+        assert_ne!(failure.expected, failure.actual);
+        assert_eq!(Some(Ok(conformance::fold(&failure.ops))), failure.expected);
+    }
+
+    #[test]
+    pub fn unsupported_sequences_are_skipped() {
+        /// Can't add collection items, and ignores booleans
+        struct Scalars;
+
+        impl Contender for Scalars {
+            fn declaration(&self) -> &Declaration {
+                &BROKEN
+            }
+        }
+
+        impl Adapter for Scalars {
+            fn apply(&self, ops: &[Op]) -> Result<Root, Unsupported> {
+                if ops.iter().any(|op| matches!(op, Op::ListItem(_) | Op::MapItem(_, _))) {
+                    return Err(Unsupported("collection items can't be added"));
+                }
+                Ok(Root { boolean: false, ..conformance::fold(ops) })
+            }
+        }
+
+        let failures = run(&Folded, &[&Scalars], 0, 500);
+
+        assert_eq!(1, failures.len());
+        let failure = &failures[0];
+        assert_eq!(vec![Op::Boolean(true)], failure.ops, "{}", failure);
+        assert_eq!(Some(Ok(Root::default())), failure.actual);
+        assert!(!supports(&Scalars, &[Op::ListItem(0)]));
+        assert!(!diverges(&Folded, &Scalars, &[Op::Boolean(true), Op::ListItem(0)]));
+    }
+}
@@ -7,24 +7,13 @@
 //!
 //! ## Comparison
 //!
-//! | Feature                                                  | [`derive-builder 0.12.0`](derivebuilder)                            | [`typed_builder 0.16.2`](typedbuilder)                             |
-//! | -----------------------------------------------          | ---------------------------------------------------------    | -------------------------------------------------------     |
-//! | [`fn builder()`](#feature---builder-function)            | [✋](derivebuilder/index.html#feature---builder-function)    | [👍](typedbuilder/index.html#feature---builder-function)     |
-//! | [`Into` field](#feature---into-field)                    | [🤏](derivebuilder/index.html#feature---into-field)           | [🤏](typedbuilder/index.html#feature---into-field)          |
-//! | [`Option` field](#feature---option-field)                | [👍](derivebuilder/index.html#feature---option-field)        | [☝](typedbuilder/index.html#feature---option-field)        |
-//! | [`Default` struct](#feature---default-struct)            | [👍](derivebuilder/index.html#feature---default-struct)      | [👎](typedbuilder/index.html#feature---default-struct)      |
-//! | [Collection field](#feature---collection-field)          | [☝](derivebuilder/index.html#feature---collection-field)    | [👎](typedbuilder/index.html#feature---collection-field)     |
-//! | [`Builder` field](#feature---builder-field)              | [✋](derivebuilder/index.html#feature---builder-field)       | [👎](typedbuilder/index.html#feature---builder-field)        |
-//! | [`Into` builder](#feature---into-builder)                | [✋](derivebuilder/index.html#feature---into-builder)        | [👎](typedbuilder/index.html#feature---into-builder)         |
-//! | [Chain call](#feature---chain-call)                      | [👍](derivebuilder/index.html#feature---chain-call)           | [👍](typedbuilder/index.html#feature---chain-call)           |
-//! | [Builder customization](#feature---builder-customization) | [👍](derivebuilder/index.html#feature---builder-customization) | [👎](typedbuilder/index.html#feature---builder-customization) |
-//!
-//! Legend:
-//! * 👍: supported (may require configuration per struct)
-//! * ☝: supported (but require field configuration)
-//! * 🤏: partially supported (see details)
-//! * ✋: not supported (but custom code possible)
-//! * 👎: not supported (and can't add custom code)
+#![doc = include_str!("matrix/comparison.md")]
+//!
+//! ### Ranking
+//!
+//! Contenders ranked by score, summing support levels (from 4 for 👍 down to 0 for 👎) weighted by `profiles/default.profile`.
+//!
+#![doc = include_str!("matrix/ranking.md")]
 //!
 //! ## Expected Features
 //!
@@ -211,6 +200,26 @@
 //! }
 //! ```
 //!
+//! ##### Feature - Builder reuse
+//!
+//! **Builder reuse**: Build several values from a same builder, used as template
+//!
+//! ```
+//! # #[derive(Default)]
+//! # struct Foobar { foo: bool }
+//! # #[derive(Default)]
+//! # struct FoobarBuilder { foo: bool }
+//! # impl FoobarBuilder {
+//! #   fn foo(&mut self, foo: bool) -> &mut Self { self.foo = foo; self }
+//! #   fn build(&self) -> Foobar { Foobar { foo: self.foo } }
+//! # }
+//! let mut builder = FoobarBuilder::default();
+//! builder.foo(true);
+//! let first = builder.build();
+//! let second = builder.build();
+//! # assert_eq!(first.foo, second.foo);
+//! ```
+//!
 //! ## Benchmarks
 //!
 //! Each crate is tested into its own module. If variants exist, they are tested into a sub-mobule.
@@ -221,10 +230,52 @@
 //! * [`derivebuilder`]: Using [`derive_builder 0.12.0` crate](https://crates.io/crates/derive_builder/0.12.0)
 //! * [`typedbuilder`]: Using [`typed-builder 0.16.2` crate](https://crates.io/crates/typed-builder/0.16.2)
 //!
+//! Each contender module is compiled under its own cargo feature, all enabled by default: `blueprint`, `derive_builder` and `typed_builder`.
+//! A contender is measured alone, with its compile time and dependencies, by disabling the others:
+//!
+//! ```text
+//! cargo build --timings --no-default-features --features typed_builder
+//! ```
+//!
+//! Tooling only registers, compares and reports enabled contenders. `just features` checks each contender alone,
+//! whereas documentation examples compare several contenders, so they require default features.
+//!
+//! ## Tooling
+//!
+//! * [`bench`]: Runtime benchmarks of every contender, printed by the `bench` binary: `just bench --format csv > bench.csv`.
+//!   Collection APIs are timed with growing item counts by `just bench --scaling`.
+//!   Timings are saved into a baseline by `just bench --save <path>`, and checked for regressions by `just bench --compare <path>`.
+//! * [`codesize`]: Machine-code size of each contender builder functions, read from the release `bench` binary: `just codesize`.
+//! * [`conformance`]: Scenarios shared by every contender, and their pass/fail grid.
+//! * [`dashboard`]: Static HTML dashboard, published with documentation by `just docs`.
+//! * [`footprint`]: `size_of`/`align_of` of builders and typed-builder typestates, next to their target: `just report --footprint`.
+//! * [`fuzz`]: Differential fuzzing of random operation sequences, against `blueprint::setter`.
+//! * [`matrix`]: Machine-readable feature matrix, declaring support levels of each contender.
+//! * [`model`]: Canonical `Root`/`Item`, every contender output converts into for comparison.
+//! * [`registry`]: Pluggable contenders, with their scenarios and benchmark hooks. Register your own to compare it.
+//! * [`report`]: Comparison report in Markdown, JSON, CSV or plain text, printed by the `report` binary:
+//!   `cargo run --bin report -- --format markdown --sort score --profile profiles/collections.profile`
+//! * [`script`]: Text scenarios from `scenarios` directory, replayed against every contender: `just report --scenario scenarios/usage.scenario`.
+//!
 
+#[cfg(feature = "derive_builder")]
 #[macro_use]
 extern crate derive_builder;
 
+pub mod bench;
+#[cfg(feature = "blueprint")]
 pub mod blueprint;
+pub mod codesize;
+pub mod conformance;
+pub mod dashboard;
+#[cfg(feature = "derive_builder")]
 pub mod derivebuilder;
+pub mod footprint;
+pub mod fuzz;
+pub mod matrix;
+pub mod model;
+pub mod registry;
+pub mod report;
+pub mod script;
+#[cfg(feature = "typed_builder")]
 pub mod typedbuilder;
@@ -0,0 +1,134 @@
+//! Tests backing support claims.
+//!
+//! Each contender registers the tests proving its claims with the [`evidence!`](crate::evidence) macro.
+//! [`unbacked_claims`] then reports every 👍, ☝ or 🤏 claim without at least one passing evidence.
+
+use std::panic;
+use super::{Declaration,Feature};
+
+/// A test proving `feature` support
+#[derive(Clone,Copy,Debug)]
+pub struct Evidence {
+    pub feature: Feature,
+    /// Test path
+    pub name: &'static str,
+    /// Test body, panicking on failure
+    pub check: fn(),
+}
+
+/// Declare tests, and register them into an `EVIDENCE` constant
+///
+/// ```rust
+/// # use rust_benchmark_setter::evidence;
+/// # use rust_benchmark_setter::matrix::Feature;
+/// evidence! {
+///     #[evidence(Feature::ChainCall, Feature::BuilderFunction)]
+///     pub fn usage() {
+///         assert!(true);
+///     }
+/// }
+///
+/// assert_eq!(2, EVIDENCE.len());
+/// assert_eq!(Feature::BuilderFunction, EVIDENCE[1].feature);
+/// ```
+#[macro_export]
+macro_rules! evidence {
+    ($(
+        #[evidence($($feature:expr),+ $(,)?)]
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident() $body:block
+    )*) => {
+        $(
+            #[cfg_attr(test, test)]
+            $(#[$attr])*
+            $vis fn $name() $body
+        )*
+
+        $crate::evidence!(@register [] $($name [$($feature),+])*);
+    };
+    (@register [$($entries:tt)*]) => {
+        /// Tests registered as evidence
+        pub const EVIDENCE: &[$crate::matrix::evidence::Evidence] = &[$($entries)*];
+    };
+    (@register [$($entries:tt)*] $name:ident [$($feature:expr),+] $($rest:tt)*) => {
+        $crate::evidence!(@register [
+            $($entries)*
+            $($crate::matrix::evidence::Evidence {
+                feature: $feature,
+                name: concat!(module_path!(), "::", stringify!($name)),
+                check: $name,
+            },)+
+        ] $($rest)*);
+    };
+}
+
+/// Run `check`, catching panic
+pub fn passes(evidence: &Evidence) -> bool {
+    panic::catch_unwind(evidence.check).is_ok()
+}
+
+/// Features claimed as supported by `declaration`, but without any passing evidence
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::{Declaration,Feature,Support};
+/// # use rust_benchmark_setter::matrix::evidence::{Evidence,unbacked_claims};
+/// let declaration = Declaration {
+///     name: "foobar",
+///     version: "0.1.0",
+///     module: "foobar",
+///     claims: &[
+///         (Feature::ChainCall, Support::Supported),
+///         (Feature::IntoField, Support::Partial),
+///         (Feature::BuilderField, Support::Unsupported),
+///     ],
+///     shapes: &[],
+/// };
+/// let evidence = [
+///     Evidence { feature: Feature::ChainCall, name: "chain_call", check: || {} },
+/// ];
+///
+/// assert_eq!(vec![Feature::IntoField], unbacked_claims(&declaration, &evidence));
+/// ```
+pub fn unbacked_claims(declaration: &Declaration, evidence: &[Evidence]) -> Vec<Feature> {
+    declaration.claims
+        .iter()
+        .filter(|(_, support)| support.is_supported())
+        .map(|(feature, _)| *feature)
+        .filter(|feature| !evidence.iter().any(|evidence| evidence.feature == *feature && passes(evidence)))
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    pub fn claims_are_backed_by_evidence() {
+        let contenders: &[(&Declaration, &[Evidence])] = &[
+            #[cfg(feature = "derive_builder")]
+            (&crate::derivebuilder::DECLARATION, crate::derivebuilder::tests::EVIDENCE),
+            #[cfg(feature = "typed_builder")]
+            (&crate::typedbuilder::DECLARATION, crate::typedbuilder::tests::EVIDENCE),
+        ];
+        for (declaration, evidence) in contenders {
+            let unbacked = unbacked_claims(declaration, evidence);
+            assert!(unbacked.is_empty(), "{} claims support without passing evidence: {:?}", declaration.name, unbacked);
+        }
+    }
+
+    #[test]
+    pub fn failing_evidence_is_ignored() {
+        let declaration = Declaration {
+            name: "foobar",
+            version: "0.1.0",
+            module: "foobar",
+            claims: &[(Feature::ChainCall, crate::matrix::Support::Supported)],
+            shapes: &[],
+        };
+        let evidence = [
+            Evidence { feature: Feature::ChainCall, name: "failing", check: || panic!("failing evidence") },
+        ];
+
+        assert_eq!(vec![Feature::ChainCall], unbacked_claims(&declaration, &evidence));
+    }
+}
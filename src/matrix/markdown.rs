@@ -0,0 +1,176 @@
+//! Markdown rendering of the feature matrix.
+//!
+//! Rendered tables are stored as doc fragments next to this module, and included into rustdoc:
+//! * `comparison.md`: crate-level comparison, see [`comparison`]
+//! * `ranking.md`: crate-level ranking with `profiles/default.profile` weights, see [`ranking`]
+//! * `<module>.md`: contender table, see [`contender`]
+//! * `<module>.shapes.md`: contender support per field shape, see [`shapes`]
+//!
+//! Fragments are checked by tests. Run `just matrix` (or `BLESS=1 cargo test matrix`) to regenerate them.
+
+use std::fmt::Write;
+use super::{Declaration,Feature,Shape,Support};
+use super::score::Ranking;
+
+/// Crate-level comparison table, followed by legend
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::{CONTENDERS,markdown};
+/// let table = markdown::comparison(CONTENDERS);
+///
+/// assert!(table.starts_with("| Feature | [`derive-builder 0.12.0`](derivebuilder) |"));
+/// ```
+pub fn comparison(contenders: &[&Declaration]) -> String {
+    let mut markdown = String::new();
+    write!(markdown, "| Feature |").unwrap();
+    for contender in contenders {
+        write!(markdown, " [`{} {}`]({}) |", contender.name, contender.version, contender.module).unwrap();
+    }
+    writeln!(markdown).unwrap();
+    write!(markdown, "| --- |").unwrap();
+    for _ in contenders {
+        write!(markdown, " --- |").unwrap();
+    }
+    writeln!(markdown).unwrap();
+    for feature in Feature::ALL {
+        write!(markdown, "| [{}](#{}) |", feature.label(), feature.anchor()).unwrap();
+        for contender in contenders {
+            write!(markdown, " [{}]({}/index.html#{}) |", cell(contender, feature), contender.module, feature.anchor()).unwrap();
+        }
+        writeln!(markdown).unwrap();
+    }
+    writeln!(markdown).unwrap();
+    writeln!(markdown, "Legend:").unwrap();
+    for support in Support::ALL {
+        writeln!(markdown, "* {}: {}", support.emoji(), support.description()).unwrap();
+    }
+    markdown
+}
+
+/// Contender table, as displayed in its module
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::markdown;
+/// # use rust_benchmark_setter::typedbuilder;
+/// let table = markdown::contender(&typedbuilder::DECLARATION);
+///
+/// assert!(table.contains("| [`fn builder()`](#feature---builder-function) | 👍 |"));
+/// ```
+pub fn contender(contender: &Declaration) -> String {
+    let mut markdown = String::new();
+    writeln!(markdown, "| Feature | [`{}`](self) |", contender.name).unwrap();
+    writeln!(markdown, "| --- | --- |").unwrap();
+    for feature in Feature::ALL {
+        writeln!(markdown, "| [{}](#{}) | {} |", feature.label(), feature.anchor(), cell(contender, feature)).unwrap();
+    }
+    markdown
+}
+
+/// Contender support drill-down, per field-level feature and field shape
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::markdown;
+/// # use rust_benchmark_setter::derivebuilder;
+/// let table = markdown::shapes(&derivebuilder::DECLARATION);
+///
+/// assert!(table.contains("| [Collection field](#feature---collection-field) | - | - | - | - | ☝ | ☝ | - |"));
+/// ```
+pub fn shapes(contender: &Declaration) -> String {
+    let mut markdown = String::new();
+    write!(markdown, "| Feature |").unwrap();
+    for shape in Shape::ALL {
+        write!(markdown, " {} |", shape.label()).unwrap();
+    }
+    writeln!(markdown).unwrap();
+    write!(markdown, "| --- |").unwrap();
+    for _ in Shape::ALL {
+        write!(markdown, " --- |").unwrap();
+    }
+    writeln!(markdown).unwrap();
+    for feature in Feature::ALL.into_iter().filter(|feature| !feature.shapes().is_empty()) {
+        write!(markdown, "| [{}](#{}) |", feature.label(), feature.anchor()).unwrap();
+        for shape in Shape::ALL {
+            write!(markdown, " {} |", contender.shape_support(feature, shape).map(|support| support.emoji()).unwrap_or("-")).unwrap();
+        }
+        writeln!(markdown).unwrap();
+    }
+    markdown
+}
+
+/// Ranking table, followed by features driving it
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::{CONTENDERS,markdown};
+/// # use rust_benchmark_setter::matrix::score::{Profile,Ranking};
+/// let ranking = Ranking::new(CONTENDERS, &Profile::uniform());
+///
+/// assert!(markdown::ranking(&ranking).contains("| 1 | [`derive-builder 0.12.0`](derivebuilder) | 28 |"));
+/// ```
+pub fn ranking(ranking: &Ranking) -> String {
+    let mut markdown = String::new();
+    writeln!(markdown, "| Rank | Contender | Score |").unwrap();
+    writeln!(markdown, "| --- | --- | --- |").unwrap();
+    for (index, score) in ranking.scores().iter().enumerate() {
+        writeln!(
+            markdown,
+            "| {} | [`{} {}`]({}) | {} |",
+            index + 1, score.contender.name, score.contender.version, score.contender.module, score.total(),
+        ).unwrap();
+    }
+    let drivers = ranking.drivers();
+    if !drivers.is_empty() {
+        writeln!(markdown).unwrap();
+        writeln!(markdown, "Ranking drivers:").unwrap();
+        for driver in drivers {
+            writeln!(markdown, "* [{}](#{}) (weight {}): {}", driver.feature.label(), driver.feature.anchor(), driver.weight, driver.summary()).unwrap();
+        }
+    }
+    markdown
+}
+
+fn cell(contender: &Declaration, feature: Feature) -> &'static str {
+    contender.support(feature).map(|support| support.emoji()).unwrap_or("")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::matrix::CONTENDERS;
+    use std::path::PathBuf;
+
+    /// Compare `rendered` with fragment `name`, or overwrite it when `BLESS` is set
+    pub fn check_fragment(name: &str, rendered: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/matrix").join(name);
+        if std::env::var_os("BLESS").is_some() {
+            std::fs::write(&path, rendered).expect("Unable to write fragment");
+            return;
+        }
+        let actual = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            actual == rendered,
+            "{} doesn't match declared support levels, run `just matrix` to regenerate it.\n--- actual\n{}\n--- expected\n{}",
+            path.display(), actual, rendered,
+        );
+    }
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn comparison_is_up_to_date() {
+        check_fragment("comparison.md", &comparison(CONTENDERS));
+    }
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn ranking_is_up_to_date() {
+        let profile = crate::matrix::score::Profile::parse(include_str!("../../profiles/default.profile")).expect("Invalid default profile");
+        check_fragment("ranking.md", &ranking(&Ranking::new(CONTENDERS, &profile)));
+    }
+
+    #[test]
+    pub fn contenders_are_up_to_date() {
+        for declaration in CONTENDERS {
+            check_fragment(&format!("{}.md", declaration.module), &contender(declaration));
+            check_fragment(&format!("{}.shapes.md", declaration.module), &shapes(declaration));
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! Machine-readable feature matrix.
+//!
+//! The [comparison](crate#comparison) is declared here as data, so support levels can be queried from code:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::matrix::{Feature,Support};
+//! # use rust_benchmark_setter::derivebuilder;
+//! assert_eq!(
+//!     Some(Support::FieldConfiguration),
+//!     derivebuilder::DECLARATION.support(Feature::CollectionField),
+//! );
+//! ```
+//!
+//! Tables displayed in documentation are rendered from these declarations by [`markdown`].
+//! Contenders are ranked by weighted [`score`].
+//! Each contender version is recorded as a [`snapshot`], to follow support changes across upgrades.
+//! Support claims are backed by tests registered as [`evidence`],
+//! while ✋ and 👎 claims are proven by compile-fail snippets, under `tests/compile-fail/<module>/<feature>.rs`.
+
+use std::fmt;
+use std::str::FromStr;
+
+pub mod evidence;
+pub mod markdown;
+pub mod score;
+pub mod snapshot;
+
+/// Expected features of a builder/setter generation crate
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub enum Feature {
+    BuilderFunction,
+    IntoField,
+    OptionField,
+    DefaultStruct,
+    CollectionField,
+    BuilderField,
+    IntoBuilder,
+    ChainCall,
+    BuilderCustomization,
+    BuilderReuse,
+}
+
+/// Shape of a field type, some features support only depending on it
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub enum Shape {
+    /// Primitive type, like `u8` or `bool`
+    Scalar,
+    String,
+    /// `Option` of primitive or `String`
+    Option,
+    /// `Option` of a sub-struct
+    OptionStruct,
+    Vec,
+    HashMap,
+    /// Sub-struct
+    Nested,
+}
+
+/// Support level of a feature, as described by the [legend](crate#comparison)
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub enum Support {
+    /// 👍: supported (may require configuration per struct)
+    Supported,
+    /// ☝: supported (but require field configuration)
+    FieldConfiguration,
+    /// 🤏: partially supported (see details)
+    Partial,
+    /// ✋: not supported (but custom code possible)
+    CustomCode,
+    /// 👎: not supported (and can't add custom code)
+    Unsupported,
+}
+
+/// Support levels claimed by a contender
+#[derive(Debug)]
+pub struct Declaration {
+    /// Crate name, as published on crates.io
+    pub name: &'static str,
+    /// Benchmarked crate version
+    pub version: &'static str,
+    /// Module of this crate holding the demo
+    pub module: &'static str,
+    /// Support level for each feature
+    pub claims: &'static [(Feature, Support)],
+    /// Support level for each field shape, when different from feature one
+    pub shapes: &'static [(Feature, Shape, Support)],
+}
+
+/// Contenders of the crate-level comparison, among enabled features
+pub const CONTENDERS: &[&Declaration] = &[
+    #[cfg(feature = "derive_builder")]
+    &crate::derivebuilder::DECLARATION,
+    #[cfg(feature = "typed_builder")]
+    &crate::typedbuilder::DECLARATION,
+];
+
+impl Feature {
+    pub const ALL: [Feature; 10] = [
+        Feature::BuilderFunction,
+        Feature::IntoField,
+        Feature::OptionField,
+        Feature::DefaultStruct,
+        Feature::CollectionField,
+        Feature::BuilderField,
+        Feature::IntoBuilder,
+        Feature::ChainCall,
+        Feature::BuilderCustomization,
+        Feature::BuilderReuse,
+    ];
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Feature;
+    /// assert_eq!("`fn builder()`", Feature::BuilderFunction.label());
+    /// ```
+    pub fn label(&self) -> &'static str {
+        match self {
+            Feature::BuilderFunction => "`fn builder()`",
+            Feature::IntoField => "`Into` field",
+            Feature::OptionField => "`Option` field",
+            Feature::DefaultStruct => "`Default` struct",
+            Feature::CollectionField => "Collection field",
+            Feature::BuilderField => "`Builder` field",
+            Feature::IntoBuilder => "`Into` builder",
+            Feature::ChainCall => "Chain call",
+            Feature::BuilderCustomization => "Builder customization",
+            Feature::BuilderReuse => "Builder reuse",
+        }
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Feature;
+    /// assert_eq!("builder-function", Feature::BuilderFunction.key());
+    /// ```
+    pub fn key(&self) -> &'static str {
+        match self {
+            Feature::BuilderFunction => "builder-function",
+            Feature::IntoField => "into-field",
+            Feature::OptionField => "option-field",
+            Feature::DefaultStruct => "default-struct",
+            Feature::CollectionField => "collection-field",
+            Feature::BuilderField => "builder-field",
+            Feature::IntoBuilder => "into-builder",
+            Feature::ChainCall => "chain-call",
+            Feature::BuilderCustomization => "builder-customization",
+            Feature::BuilderReuse => "builder-reuse",
+        }
+    }
+
+    /// Field shapes relevant for this feature, empty for struct-level features
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{Feature,Shape};
+    /// assert_eq!(&[Shape::Vec, Shape::HashMap], Feature::CollectionField.shapes());
+    /// assert!(Feature::ChainCall.shapes().is_empty());
+    /// ```
+    pub fn shapes(&self) -> &'static [Shape] {
+        match self {
+            Feature::IntoField => &Shape::ALL,
+            Feature::OptionField => &[Shape::Option, Shape::OptionStruct],
+            Feature::CollectionField => &[Shape::Vec, Shape::HashMap],
+            Feature::BuilderField => &[Shape::OptionStruct, Shape::Vec, Shape::HashMap, Shape::Nested],
+            _ => &[],
+        }
+    }
+
+    /// Rustdoc anchor of the feature details section
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Feature;
+    /// assert_eq!("feature---builder-function", Feature::BuilderFunction.anchor());
+    /// ```
+    pub fn anchor(&self) -> String {
+        format!("feature---{}", self.key())
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Parse a feature from its [key](Feature::key)
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::Feature;
+/// assert_eq!(Ok(Feature::ChainCall), "chain-call".parse());
+/// assert!("chaincall".parse::<Feature>().is_err());
+/// ```
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.key() == key)
+            .ok_or_else(|| format!("unknown feature '{}'", key))
+    }
+}
+
+impl Shape {
+    pub const ALL: [Shape; 7] = [
+        Shape::Scalar,
+        Shape::String,
+        Shape::Option,
+        Shape::OptionStruct,
+        Shape::Vec,
+        Shape::HashMap,
+        Shape::Nested,
+    ];
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Shape;
+    /// assert_eq!("`HashMap<K,V>`", Shape::HashMap.label());
+    /// ```
+    pub fn label(&self) -> &'static str {
+        match self {
+            Shape::Scalar => "scalar",
+            Shape::String => "`String`",
+            Shape::Option => "`Option<T>`",
+            Shape::OptionStruct => "`Option<Struct>`",
+            Shape::Vec => "`Vec<T>`",
+            Shape::HashMap => "`HashMap<K,V>`",
+            Shape::Nested => "nested struct",
+        }
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Shape;
+    /// assert_eq!("option-struct", Shape::OptionStruct.key());
+    /// ```
+    pub fn key(&self) -> &'static str {
+        match self {
+            Shape::Scalar => "scalar",
+            Shape::String => "string",
+            Shape::Option => "option",
+            Shape::OptionStruct => "option-struct",
+            Shape::Vec => "vec",
+            Shape::HashMap => "hash-map",
+            Shape::Nested => "nested",
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Parse a field shape from its [key](Shape::key)
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::Shape;
+/// assert_eq!(Ok(Shape::Vec), "vec".parse());
+/// ```
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        Shape::ALL
+            .into_iter()
+            .find(|shape| shape.key() == key)
+            .ok_or_else(|| format!("unknown field shape '{}'", key))
+    }
+}
+
+impl Support {
+    pub const ALL: [Support; 5] = [
+        Support::Supported,
+        Support::FieldConfiguration,
+        Support::Partial,
+        Support::CustomCode,
+        Support::Unsupported,
+    ];
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Support;
+    /// assert_eq!("👍", Support::Supported.emoji());
+    /// ```
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Support::Supported => "👍",
+            Support::FieldConfiguration => "☝",
+            Support::Partial => "🤏",
+            Support::CustomCode => "✋",
+            Support::Unsupported => "👎",
+        }
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Support;
+    /// assert_eq!("supported", Support::Supported.key());
+    /// ```
+    pub fn key(&self) -> &'static str {
+        match self {
+            Support::Supported => "supported",
+            Support::FieldConfiguration => "field-configuration",
+            Support::Partial => "partial",
+            Support::CustomCode => "custom-code",
+            Support::Unsupported => "unsupported",
+        }
+    }
+
+    /// Score used for ranking, from `4` (👍) down to `0` (👎)
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Support;
+    /// assert!(Support::Supported.score() > Support::Partial.score());
+    /// assert_eq!(0, Support::Unsupported.score());
+    /// ```
+    pub fn score(&self) -> u32 {
+        match self {
+            Support::Supported => 4,
+            Support::FieldConfiguration => 3,
+            Support::Partial => 2,
+            Support::CustomCode => 1,
+            Support::Unsupported => 0,
+        }
+    }
+
+    /// Whether feature is available, at least partially, without custom code
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Support;
+    /// assert!(Support::Partial.is_supported());
+    /// assert!(!Support::CustomCode.is_supported());
+    /// ```
+    pub fn is_supported(&self) -> bool {
+        matches!(self, Support::Supported | Support::FieldConfiguration | Support::Partial)
+    }
+
+    /// Legend description
+    pub fn description(&self) -> &'static str {
+        match self {
+            Support::Supported => "supported (may require configuration per struct)",
+            Support::FieldConfiguration => "supported (but require field configuration)",
+            Support::Partial => "partially supported (see details)",
+            Support::CustomCode => "not supported (but custom code possible)",
+            Support::Unsupported => "not supported (and can't add custom code)",
+        }
+    }
+}
+
+impl fmt::Display for Support {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.emoji())
+    }
+}
+
+/// Parse a support level from its [key](Support::key) or [emoji](Support::emoji)
+///
+/// ```rust
+/// # use rust_benchmark_setter::matrix::Support;
+/// assert_eq!(Ok(Support::Partial), "partial".parse());
+/// assert_eq!(Ok(Support::Partial), "🤏".parse());
+/// ```
+impl FromStr for Support {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Support::ALL
+            .into_iter()
+            .find(|support| support.key() == value || support.emoji() == value)
+            .ok_or_else(|| format!("unknown support level '{}'", value))
+    }
+}
+
+impl Declaration {
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{Feature,Support};
+    /// # use rust_benchmark_setter::typedbuilder;
+    /// assert_eq!(
+    ///     Some(Support::Supported),
+    ///     typedbuilder::DECLARATION.support(Feature::BuilderFunction),
+    /// );
+    /// ```
+    pub fn support(&self, feature: Feature) -> Option<Support> {
+        self.claims
+            .iter()
+            .find(|(claimed, _)| *claimed == feature)
+            .map(|(_, support)| *support)
+    }
+
+    /// Support level of `feature` for fields of given `shape`, `None` when not relevant
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{Feature,Shape,Support};
+    /// # use rust_benchmark_setter::derivebuilder;
+    /// assert_eq!(Some(Support::Supported), derivebuilder::DECLARATION.shape_support(Feature::IntoField, Shape::Vec));
+    /// assert_eq!(Some(Support::Partial), derivebuilder::DECLARATION.shape_support(Feature::IntoField, Shape::HashMap));
+    /// assert_eq!(None, derivebuilder::DECLARATION.shape_support(Feature::CollectionField, Shape::String));
+    /// ```
+    pub fn shape_support(&self, feature: Feature, shape: Shape) -> Option<Support> {
+        if !feature.shapes().contains(&shape) {
+            return None;
+        }
+        self.shapes
+            .iter()
+            .find(|(claimed, claimed_shape, _)| *claimed == feature && *claimed_shape == shape)
+            .map(|(_, _, support)| *support)
+            .or_else(|| self.support(feature))
+    }
+
+    /// Sum of [support scores](Support::score) over `features`
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Feature;
+    /// # use rust_benchmark_setter::typedbuilder;
+    /// assert_eq!(4, typedbuilder::DECLARATION.score(&[Feature::BuilderFunction]));
+    /// ```
+    pub fn score(&self, features: &[Feature]) -> u32 {
+        features
+            .iter()
+            .filter_map(|feature| self.support(*feature))
+            .map(|support| support.score())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    pub fn contenders_declare_every_feature() {
+        for contender in CONTENDERS {
+            for feature in Feature::ALL {
+                assert!(
+                    contender.support(feature).is_some(),
+                    "{} doesn't declare {:?}", contender.name, feature,
+                );
+            }
+            assert_eq!(Feature::ALL.len(), contender.claims.len(), "{} declares duplicates", contender.name);
+        }
+    }
+
+    #[test]
+    pub fn contenders_declare_relevant_shapes() {
+        for contender in CONTENDERS {
+            for (feature, shape, _) in contender.shapes {
+                assert!(feature.shapes().contains(shape), "{} declares {:?} for {:?}", contender.name, feature, shape);
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Weighted scoring and ranking of contenders.
+//!
+//! Feature weights are read from a profile file, one `<feature> = <weight>` line per feature.
+//! Blank lines and `#` comments are ignored, unlisted features weight `1`, and weights are at most [`MAX_WEIGHT`]:
+//!
+//! ```text
+//! # We care far more about collections than builder function
+//! collection-field = 5
+//! builder-field = 5
+//! builder-function = 0
+//! ```
+//!
+//! A contender score is the sum, over features, of `weight × support score` (see [`Support::score`](super::Support::score)).
+//!
+//! ```rust
+//! # use rust_benchmark_setter::matrix::CONTENDERS;
+//! # use rust_benchmark_setter::matrix::score::{Profile,Ranking};
+//! let profile = Profile::parse("collection-field = 5\nbuilder-function = 0").unwrap();
+//! let ranking = Ranking::new(CONTENDERS, &profile);
+//!
+//! assert_eq!("derive-builder", ranking.scores()[0].contender.name);
+//! assert_eq!(
+//!     "Collection field (weight 5): derive-builder ☝ 15, typed-builder 👎 0",
+//!     ranking.drivers()[0].to_string(),
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use super::{Declaration,Feature};
+
+/// Highest feature weight, keeping weighted scores well within `u32`
+pub const MAX_WEIGHT: u32 = 1000;
+
+/// Feature weights
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Profile {
+    weights: BTreeMap<Feature, u32>,
+}
+
+/// Invalid profile line
+#[derive(Clone,Debug,PartialEq)]
+pub struct ProfileError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+/// Weighted score of a contender
+#[derive(Clone,Debug)]
+pub struct Score<'a> {
+    pub contender: &'a Declaration,
+    /// Weighted score, per feature
+    pub contributions: Vec<(Feature, u32)>,
+}
+
+/// Contenders sorted by descending weighted score
+#[derive(Clone,Debug)]
+pub struct Ranking<'a> {
+    features: Vec<Feature>,
+    profile: Profile,
+    scores: Vec<Score<'a>>,
+}
+
+/// Feature splitting contenders apart
+#[derive(Clone,Debug)]
+pub struct Driver<'a> {
+    pub feature: Feature,
+    pub weight: u32,
+    /// Weighted score gap between best and worst contenders
+    pub spread: u32,
+    ranking: &'a Ranking<'a>,
+}
+
+impl Profile {
+    /// All features weight `1`
+    pub fn uniform() -> Self {
+        Self::default()
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::Feature;
+    /// # use rust_benchmark_setter::matrix::score::Profile;
+    /// let profile = Profile::parse("# comment\n\nchain-call = 3").unwrap();
+    ///
+    /// assert_eq!(3, profile.weight(Feature::ChainCall));
+    /// assert_eq!(1, profile.weight(Feature::IntoField));
+    ///
+    /// let error = Profile::parse("chain-call = high").unwrap_err();
+    /// assert_eq!("line 1: invalid weight 'high'", error.to_string());
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, ProfileError> {
+        let mut profile = Self::default();
+        for (index, line) in source.lines().enumerate() {
+            let error = |message: String| ProfileError { line: index + 1, message };
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, weight) = line.split_once('=').ok_or_else(|| error(format!("expected '<feature> = <weight>', found '{}'", line)))?;
+            let feature = key.trim().parse::<Feature>().map_err(error)?;
+            let weight = weight.trim().parse::<u32>().map_err(|_| error(format!("invalid weight '{}'", weight.trim())))?;
+            if weight > MAX_WEIGHT {
+                return Err(error(format!("weight {} above maximum {}", weight, MAX_WEIGHT)));
+            }
+            profile.weights.insert(feature, weight);
+        }
+        Ok(profile)
+    }
+
+    pub fn weight(&self, feature: Feature) -> u32 {
+        self.weights.get(&feature).copied().unwrap_or(1)
+    }
+
+    /// Weighted score of `contender` for `feature`
+    pub fn score(&self, contender: &Declaration, feature: Feature) -> u32 {
+        self.weight(feature) * contender.support(feature).map(|support| support.score()).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ProfileError {}
+
+impl<'a> Score<'a> {
+    pub fn total(&self) -> u32 {
+        self.contributions.iter().map(|(_, score)| score).sum()
+    }
+
+    pub fn contribution(&self, feature: Feature) -> u32 {
+        self.contributions
+            .iter()
+            .find(|(scored, _)| *scored == feature)
+            .map(|(_, score)| *score)
+            .unwrap_or(0)
+    }
+}
+
+impl<'a> Ranking<'a> {
+    /// Rank `contenders` over all features
+    pub fn new(contenders: &[&'a Declaration], profile: &Profile) -> Self {
+        Self::with_features(contenders, profile, &Feature::ALL)
+    }
+
+    /// Rank `contenders` over `features` only
+    pub fn with_features(contenders: &[&'a Declaration], profile: &Profile, features: &[Feature]) -> Self {
+        let mut scores = contenders
+            .iter()
+            .map(|contender| Score {
+                contender,
+                contributions: features.iter().map(|feature| (*feature, profile.score(contender, *feature))).collect(),
+            })
+            .collect::<Vec<_>>();
+        scores.sort_by_key(|score| std::cmp::Reverse(score.total()));
+        Self {
+            features: features.to_vec(),
+            profile: profile.clone(),
+            scores,
+        }
+    }
+
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn scores(&self) -> &[Score<'a>] {
+        &self.scores
+    }
+
+    /// Weighted score of `contender`, matched by name
+    pub fn score(&self, contender: &Declaration) -> u32 {
+        self.scores
+            .iter()
+            .find(|score| score.contender.name == contender.name)
+            .map(|score| score.total())
+            .unwrap_or(0)
+    }
+
+    /// Features by descending spread, skipping features not splitting contenders apart
+    pub fn drivers(&self) -> Vec<Driver<'_>> {
+        let mut drivers = self.features
+            .iter()
+            .map(|feature| {
+                let contributions = self.scores.iter().map(|score| score.contribution(*feature));
+                let spread = contributions.clone().max().unwrap_or(0) - contributions.min().unwrap_or(0);
+                Driver {
+                    feature: *feature,
+                    weight: self.profile.weight(*feature),
+                    spread,
+                    ranking: self,
+                }
+            })
+            .filter(|driver| driver.spread > 0)
+            .collect::<Vec<_>>();
+        drivers.sort_by_key(|driver| std::cmp::Reverse(driver.spread));
+        drivers
+    }
+}
+
+impl<'a> Driver<'a> {
+    /// Contenders support and weighted score, best first
+    pub fn summary(&self) -> String {
+        let mut scores = self.ranking.scores.iter().collect::<Vec<_>>();
+        scores.sort_by_key(|score| std::cmp::Reverse(score.contribution(self.feature)));
+        scores
+            .iter()
+            .map(|score| format!(
+                "{} {} {}",
+                score.contender.name,
+                score.contender.support(self.feature).map(|support| support.emoji()).unwrap_or("-"),
+                score.contribution(self.feature),
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl<'a> fmt::Display for Driver<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (weight {}): {}", self.feature.label(), self.weight, self.summary())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::matrix::CONTENDERS;
+
+    #[test]
+    pub fn uniform_profile_matches_plain_score() {
+        let ranking = Ranking::new(CONTENDERS, &Profile::uniform());
+
+        for contender in CONTENDERS {
+            assert_eq!(contender.score(&Feature::ALL), ranking.score(contender));
+        }
+    }
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn weights_change_ranking() {
+        let profile = Profile::parse("builder-function = 10\nchain-call = 0").unwrap();
+        let ranking = Ranking::with_features(CONTENDERS, &profile, &[Feature::BuilderFunction, Feature::ChainCall, Feature::OptionField]);
+
+        assert_eq!("typed-builder", ranking.scores()[0].contender.name);
+        let drivers = ranking.drivers().iter().map(|driver| driver.feature).collect::<Vec<_>>();
+        assert_eq!(vec![Feature::BuilderFunction, Feature::OptionField], drivers);
+    }
+
+    #[cfg(feature = "derive_builder")]
+    #[test]
+    pub fn score_of_declaration_copy() {
+        let ranking = Ranking::new(CONTENDERS, &Profile::uniform());
+        let declaration = crate::derivebuilder::DECLARATION;
+
+        assert_eq!(declaration.score(&Feature::ALL), ranking.score(&declaration));
+        assert_ne!(0, ranking.score(&declaration));
+    }
+
+    #[test]
+    pub fn profiles_are_valid() {
+        Profile::parse(include_str!("../../profiles/default.profile")).expect("Invalid default profile");
+        Profile::parse(include_str!("../../profiles/collections.profile")).expect("Invalid collections profile");
+    }
+
+    #[test]
+    pub fn invalid_profile() {
+        assert_eq!(
+            Err(ProfileError { line: 2, message: "unknown feature 'foo'".to_owned() }),
+            Profile::parse("chain-call = 1\nfoo = 1"),
+        );
+        assert_eq!(
+            Err(ProfileError { line: 1, message: "expected '<feature> = <weight>', found 'chain-call'".to_owned() }),
+            Profile::parse("chain-call"),
+        );
+    }
+
+    #[test]
+    pub fn weight_above_maximum() {
+        assert_eq!(
+            Err(ProfileError { line: 1, message: "weight 4000000000 above maximum 1000".to_owned() }),
+            Profile::parse("chain-call = 4000000000"),
+        );
+
+        let profile = Profile::parse(&Feature::ALL.iter().map(|feature| format!("{} = {}", feature.key(), MAX_WEIGHT)).collect::<Vec<_>>().join("\n")).unwrap();
+        let ranking = Ranking::new(CONTENDERS, &profile);
+        for contender in CONTENDERS {
+            assert_eq!(MAX_WEIGHT * contender.score(&Feature::ALL), ranking.score(contender));
+        }
+    }
+}
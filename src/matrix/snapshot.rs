@@ -0,0 +1,334 @@
+//! Versioned snapshots of a contender matrix column, and their diff.
+//!
+//! A snapshot is recorded per contender version, into `snapshots/<name>-<version>.txt`.
+//! Tests fail when the snapshot of a declared version is missing or outdated: run `just matrix` to record it.
+//!
+//! ```text
+//! contender derive-builder
+//! version 0.12.0
+//! feature builder-function custom-code 0/0
+//! feature into-field partial 1/1
+//! timing usage 118.4
+//! ```
+//!
+//! Each `feature` line holds the support level, then the passing / registered [evidence](super::evidence) count.
+//! Each `timing` line holds the median ns/op of a [benchmark](crate::bench) scenario, recorded by `just bench --snapshot snapshots`.
+//! Timings depend on the recording machine: tests only check feature lines, and keep recorded timings when blessing.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::matrix::Feature;
+//! # use rust_benchmark_setter::matrix::snapshot::Snapshot;
+//! let before = Snapshot::parse("contender foo\nversion 0.1.0\nfeature collection-field unsupported 0/0").unwrap();
+//! let after = Snapshot::parse("contender foo\nversion 0.2.0\nfeature collection-field field-configuration 1/1").unwrap();
+//!
+//! let changes = before.diff(&after);
+//! assert_eq!(1, changes.len());
+//! assert_eq!("Collection field: 👎 → ☝ (evidence 0/0 → 1/1)", changes[0].to_string());
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self,Write};
+use super::{Declaration,Feature,Support};
+use super::evidence::{self,Evidence};
+use crate::bench::Timing;
+
+/// Recorded state of a matrix cell
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Cell {
+    pub support: Support,
+    /// Passing evidence count
+    pub passed: usize,
+    /// Registered evidence count
+    pub registered: usize,
+}
+
+/// Recorded matrix column of a contender version
+#[derive(Clone,Debug,PartialEq)]
+pub struct Snapshot {
+    pub contender: String,
+    pub version: String,
+    pub cells: BTreeMap<Feature, Cell>,
+    /// Median ns/op, by benchmark scenario
+    pub timings: BTreeMap<String, f64>,
+}
+
+/// Invalid snapshot line
+#[derive(Clone,Debug,PartialEq)]
+pub struct SnapshotError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+/// Cell moved between two snapshots
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Change {
+    pub feature: Feature,
+    pub before: Option<Cell>,
+    pub after: Option<Cell>,
+}
+
+/// Benchmark timing moved between two snapshots
+#[derive(Clone,Debug,PartialEq)]
+pub struct TimingChange {
+    pub scenario: String,
+    /// Median ns/op
+    pub before: Option<f64>,
+    /// Median ns/op
+    pub after: Option<f64>,
+}
+
+impl Snapshot {
+    /// Record `declaration` claims, running its `evidence`
+    pub fn record(declaration: &Declaration, evidence: &[Evidence]) -> Self {
+        let cells = declaration.claims
+            .iter()
+            .map(|(feature, support)| {
+                let backing = evidence.iter().filter(|evidence| evidence.feature == *feature).collect::<Vec<_>>();
+                (*feature, Cell {
+                    support: *support,
+                    passed: backing.iter().filter(|evidence| evidence::passes(evidence)).count(),
+                    registered: backing.len(),
+                })
+            })
+            .collect();
+        Self {
+            contender: declaration.name.to_owned(),
+            version: declaration.version.to_owned(),
+            cells,
+            timings: BTreeMap::new(),
+        }
+    }
+
+    /// Replace timings with median of `timings` from same contender
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::bench::Timing;
+    /// # use rust_benchmark_setter::matrix::snapshot::Snapshot;
+    /// let snapshot = Snapshot::parse("contender foo\nversion 0.1.0\ntiming usage 20.0").unwrap();
+    /// let timing = |contender, nanos| Timing { contender, scenario: "usage", iterations: 1024, nanos, summary: None, allocations: None, bytes: None };
+    ///
+    /// let snapshot = snapshot.with_timings(&[timing("foo", 12.5), timing("bar", 30.0)]);
+    /// assert_eq!("contender foo\nversion 0.1.0\ntiming usage 12.5\n", snapshot.render());
+    /// ```
+    pub fn with_timings(mut self, timings: &[Timing]) -> Self {
+        self.timings = timings
+            .iter()
+            .filter(|timing| timing.contender == self.contender)
+            .map(|timing| (timing.scenario.to_owned(), timing.nanos))
+            .collect();
+        self
+    }
+
+    /// File name, relative to `snapshots` directory
+    pub fn file_name(&self) -> String {
+        format!("{}-{}.txt", self.contender, self.version)
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "contender {}", self.contender).unwrap();
+        writeln!(text, "version {}", self.version).unwrap();
+        for (feature, cell) in &self.cells {
+            writeln!(text, "feature {} {} {}/{}", feature.key(), cell.support.key(), cell.passed, cell.registered).unwrap();
+        }
+        for (scenario, nanos) in &self.timings {
+            writeln!(text, "timing {} {:.1}", scenario, nanos).unwrap();
+        }
+        text
+    }
+
+    pub fn parse(source: &str) -> Result<Self, SnapshotError> {
+        let mut contender = None;
+        let mut version = None;
+        let mut cells = BTreeMap::new();
+        let mut timings = BTreeMap::new();
+        for (index, line) in source.lines().enumerate() {
+            let error = |message: String| SnapshotError { line: index + 1, message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["contender", name] => contender = Some(name.to_string()),
+                ["version", value] => version = Some(value.to_string()),
+                ["feature", feature, support, evidence] => {
+                    let feature = feature.parse::<Feature>().map_err(error)?;
+                    let support = support.parse::<Support>().map_err(error)?;
+                    let (passed, registered) = evidence
+                        .split_once('/')
+                        .and_then(|(passed, registered)| Some((passed.parse().ok()?, registered.parse().ok()?)))
+                        .ok_or_else(|| error(format!("invalid evidence count '{}'", evidence)))?;
+                    cells.insert(feature, Cell { support, passed, registered });
+                },
+                ["timing", scenario, nanos] => {
+                    let nanos = nanos.parse().ok().filter(|nanos: &f64| nanos.is_finite()).ok_or_else(|| error(format!("invalid timing '{}'", nanos)))?;
+                    timings.insert(scenario.to_string(), nanos);
+                },
+                _ => return Err(error(format!("unexpected line '{}'", line))),
+            }
+        }
+        let missing = |key: &str| SnapshotError { line: 0, message: format!("missing {}", key) };
+        Ok(Self {
+            contender: contender.ok_or_else(|| missing("contender"))?,
+            version: version.ok_or_else(|| missing("version"))?,
+            cells,
+            timings,
+        })
+    }
+
+    /// Cells moved from `self` to `other`, in matrix order
+    pub fn diff(&self, other: &Snapshot) -> Vec<Change> {
+        Feature::ALL
+            .into_iter()
+            .map(|feature| Change {
+                feature,
+                before: self.cells.get(&feature).copied(),
+                after: other.cells.get(&feature).copied(),
+            })
+            .filter(|change| change.before != change.after)
+            .collect()
+    }
+
+    /// Timings moved from `self` to `other`, by scenario name
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::snapshot::Snapshot;
+    /// let before = Snapshot::parse("contender foo\nversion 0.1.0\ntiming usage 20.0").unwrap();
+    /// let after = Snapshot::parse("contender foo\nversion 0.2.0\ntiming usage 15.0\ntiming default 5.0").unwrap();
+    ///
+    /// let changes = before.timing_diff(&after).iter().map(|change| change.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["default: - → 5.0 ns/op", "usage: 20.0 → 15.0 ns/op (-25.0%)"], changes);
+    /// ```
+    pub fn timing_diff(&self, other: &Snapshot) -> Vec<TimingChange> {
+        let mut scenarios = self.timings.keys().chain(other.timings.keys()).collect::<Vec<_>>();
+        scenarios.sort();
+        scenarios.dedup();
+        scenarios
+            .into_iter()
+            .map(|scenario| TimingChange {
+                scenario: scenario.clone(),
+                before: self.timings.get(scenario).copied(),
+                after: other.timings.get(scenario).copied(),
+            })
+            .filter(|change| change.before != change.after)
+            .collect()
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let support = |cell: Option<Cell>| cell.map(|cell| cell.support.emoji()).unwrap_or("-");
+        let evidence = |cell: Option<Cell>| cell.map(|cell| format!("{}/{}", cell.passed, cell.registered)).unwrap_or_else(|| "-".to_owned());
+        write!(f, "{}: {} → {}", self.feature.label(), support(self.before), support(self.after))?;
+        if evidence(self.before) != evidence(self.after) {
+            write!(f, " (evidence {} → {})", evidence(self.before), evidence(self.after))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = |nanos: Option<f64>| nanos.map(|nanos| format!("{:.1}", nanos)).unwrap_or_else(|| "-".to_owned());
+        write!(f, "{}: {} → {} ns/op", self.scenario, nanos(self.before), nanos(self.after))?;
+        if let (Some(before), Some(after)) = (self.before, self.after) {
+            write!(f, " ({:+.1}%)", (after / before - 1.0) * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn contenders() -> Vec<(&'static Declaration, &'static [Evidence])> {
+        vec![
+            #[cfg(feature = "derive_builder")]
+            (&crate::derivebuilder::DECLARATION, crate::derivebuilder::tests::EVIDENCE),
+            #[cfg(feature = "typed_builder")]
+            (&crate::typedbuilder::DECLARATION, crate::typedbuilder::tests::EVIDENCE),
+        ]
+    }
+
+    #[test]
+    pub fn snapshots_are_recorded() {
+        for (declaration, evidence) in contenders() {
+            let mut snapshot = Snapshot::record(declaration, evidence);
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots").join(snapshot.file_name());
+            if std::env::var_os("BLESS").is_some() {
+                if let Some(recorded) = std::fs::read_to_string(&path).ok().and_then(|recorded| Snapshot::parse(&recorded).ok()) {
+                    snapshot.timings = recorded.timings;
+                }
+                std::fs::write(&path, snapshot.render()).expect("Unable to write snapshot");
+                continue;
+            }
+            let recorded = std::fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("No snapshot for {} {}, run `just matrix` to record it.", declaration.name, declaration.version));
+            let recorded = Snapshot::parse(&recorded).unwrap_or_else(|error| panic!("Invalid snapshot {}: {}", path.display(), error));
+            let changes = recorded.diff(&snapshot).iter().map(|change| change.to_string()).collect::<Vec<_>>();
+            assert!(
+                changes.is_empty(),
+                "{} is outdated, run `just matrix` to record it:\n{}",
+                path.display(), changes.join("\n"),
+            );
+        }
+    }
+
+    #[test]
+    pub fn versions_match_manifest() {
+        let manifest = include_str!("../../Cargo.toml");
+        let dependencies = manifest.split("\n[dependencies]\n").nth(1).expect("No dependencies in Cargo.toml");
+        for (declaration, _) in contenders() {
+            // Version is the first quoted value, either `"x.y.z"` or `{ version = "x.y.z", ... }`
+            let dependency = dependencies
+                .lines()
+                .take_while(|line| !line.starts_with('['))
+                .filter_map(|line| line.split_once('='))
+                .find(|(key, _)| key.trim().replace('_', "-") == declaration.name)
+                .and_then(|(_, value)| value.split('"').nth(1))
+                .unwrap_or_else(|| panic!("{} isn't a dependency", declaration.name));
+            assert_eq!(dependency, declaration.version, "{} declared version doesn't match Cargo.toml", declaration.name);
+        }
+    }
+
+    #[test]
+    pub fn render_and_parse() {
+        for (declaration, evidence) in contenders() {
+            let mut snapshot = Snapshot::record(declaration, evidence);
+            snapshot.timings.insert("usage".to_owned(), 118.4);
+
+            assert_eq!(Ok(snapshot.clone()), Snapshot::parse(&snapshot.render()));
+            assert!(snapshot.diff(&snapshot).is_empty());
+            assert!(snapshot.timing_diff(&snapshot).is_empty());
+        }
+    }
+
+    #[test]
+    pub fn invalid_snapshot() {
+        assert_eq!(
+            Err(SnapshotError { line: 2, message: "invalid evidence count '1'".to_owned() }),
+            Snapshot::parse("contender foo\nfeature chain-call supported 1"),
+        );
+        assert_eq!(
+            Err(SnapshotError { line: 0, message: "missing version".to_owned() }),
+            Snapshot::parse("contender foo"),
+        );
+        assert_eq!(
+            Err(SnapshotError { line: 3, message: "invalid timing 'fast'".to_owned() }),
+            Snapshot::parse("contender foo\nversion 0.1.0\ntiming usage fast"),
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Canonical data model, every contender output converts into.
+//!
+//! Every contender declares its data structures with [`benchmark_model!`](crate::benchmark_model), so they share the same fields.
+//! Only attributes and map type differ, and the same logical value built through any contender compares equal:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::{blueprint,derivebuilder,model};
+//! let setter = model::Root::from(blueprint::setter::usage());
+//! let builder = model::Root::from(derivebuilder::usage());
+//!
+//! assert_eq!(setter, builder);
+//! assert_eq!(Some(model::Item::number(2)), builder.opt_item);
+//! ```
+//!
+//! Maps are ordered, so that models print the same whatever the insertion order.
+
+use std::collections::BTreeMap;
+
+/// Declare benchmark `Root` and `Item` data structures, with contender attributes
+///
+/// Attributes apply on both structs, then bracketed attributes on fields of given kind.
+/// Conversions into the canonical model are implemented alongside.
+///
+/// ```rust
+/// # use rust_benchmark_setter::{benchmark_model,model};
+/// # use std::collections::HashMap;
+/// benchmark_model! {
+///     #[derive(Clone,Debug,Default)]
+///     map: HashMap,
+///     option: [],
+///     listitems: [],
+///     mapitems: [],
+/// }
+///
+/// let root = Root {
+///     opt_item: Some(Item { number: 1, ..Item::default() }),
+///     ..Root::default()
+/// };
+/// assert_eq!(Some(model::Item::number(1)), model::Root::from(root).opt_item);
+/// ```
+#[macro_export]
+macro_rules! benchmark_model {
+    (
+        @structs
+        $(#[$attr:meta])*
+        map: $map:ident,
+        option: [$(#[$option:meta])*],
+        listitems: [$(#[$listitems:meta])*],
+        mapitems: [$(#[$mapitems:meta])*] $(,)?
+    ) => {
+        /// Root data structure
+        $(#[$attr])*
+        pub struct Root {
+            pub number: u8,
+            pub boolean: bool,
+            pub string: String,
+            $(#[$option])*
+            pub opt_string: Option<String>,
+            $(#[$option])*
+            pub opt_item: Option<Item>,
+            $(#[$listitems])*
+            pub listitems: Vec<Item>,
+            $(#[$mapitems])*
+            pub mapitems: $map<String, Item>,
+        }
+
+        /// Sub-item for Root data structure
+        $(#[$attr])*
+        pub struct Item {
+            pub number: u8,
+            pub boolean: bool,
+            pub string: String,
+            $(#[$option])*
+            pub opt_string: Option<String>,
+        }
+    };
+    ($($model:tt)*) => {
+        $crate::benchmark_model! { @structs $($model)* }
+
+        impl From<Root> for $crate::model::Root {
+            fn from(root: Root) -> Self {
+                Self {
+                    number: root.number,
+                    boolean: root.boolean,
+                    string: root.string,
+                    opt_string: root.opt_string,
+                    opt_item: root.opt_item.map(Into::into),
+                    listitems: root.listitems.into_iter().map(Into::into).collect(),
+                    mapitems: root.mapitems.into_iter().map(|(key, item)| (key, item.into())).collect(),
+                }
+            }
+        }
+
+        impl From<Item> for $crate::model::Item {
+            fn from(item: Item) -> Self {
+                Self {
+                    number: item.number,
+                    boolean: item.boolean,
+                    string: item.string,
+                    opt_string: item.opt_string,
+                }
+            }
+        }
+    };
+}
+
+crate::benchmark_model! {
+    @structs
+    #[derive(Clone,Debug,Default,Eq,PartialEq)]
+    map: BTreeMap,
+    option: [],
+    listitems: [],
+    mapitems: [],
+}
+
+impl Item {
+    /// Item with only `number` set
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::model::Item;
+    /// assert_eq!(Item { number: 1, ..Item::default() }, Item::number(1));
+    /// ```
+    pub fn number(number: u8) -> Self {
+        Self {
+            number,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Usage of enabled contenders, converted into canonical model
+    pub fn usages() -> Vec<(&'static str, Root)> {
+        vec![
+            #[cfg(feature = "blueprint")]
+            ("blueprint-setter", crate::blueprint::setter::usage().into()),
+            #[cfg(feature = "blueprint")]
+            ("blueprint-builder", crate::blueprint::builder::usage().into()),
+            #[cfg(feature = "derive_builder")]
+            ("derive-builder", crate::derivebuilder::usage().into()),
+            #[cfg(feature = "typed_builder")]
+            ("typed-builder", crate::typedbuilder::usage().into()),
+        ]
+    }
+
+    #[test]
+    pub fn usages_are_equivalent() {
+        let expected = Root {
+            number: 1,
+            boolean: true,
+            string: "foo".to_owned(),
+            opt_string: Some("bar".to_owned()),
+            opt_item: Some(Item::number(2)),
+            listitems: vec![Item::number(3)],
+            mapitems: BTreeMap::from([("foobar".to_owned(), Item::number(4))]),
+        };
+        for (contender, actual) in usages() {
+            assert_eq!(expected, actual, "{} usage", contender);
+        }
+    }
+
+    #[test]
+    pub fn defaults_are_equivalent() {
+        let defaults: Vec<(&str, Root)> = vec![
+            #[cfg(feature = "blueprint")]
+            ("blueprint-setter", crate::blueprint::setter::Root::default().into()),
+            #[cfg(feature = "blueprint")]
+            ("blueprint-builder", crate::blueprint::builder::Root::builder().build().into()),
+            #[cfg(feature = "derive_builder")]
+            ("derive-builder", crate::derivebuilder::Root::builder().build().expect("Unable to build root").into()),
+            #[cfg(feature = "typed_builder")]
+            ("typed-builder", crate::typedbuilder::Root::builder().build().into()),
+        ];
+        for (contender, actual) in defaults {
+            assert_eq!(Root::default(), actual, "{} default", contender);
+        }
+    }
+
+    #[cfg(feature = "blueprint")]
+    #[test]
+    pub fn blueprint_items_keep_every_field() {
+        let item = crate::blueprint::setter::Item {
+            number: 1,
+            boolean: true,
+            string: "foo".to_owned(),
+            opt_string: Some("bar".to_owned()),
+        };
+
+        assert_eq!(
+            Item {
+                number: 1,
+                boolean: true,
+                string: "foo".to_owned(),
+                opt_string: Some("bar".to_owned()),
+            },
+            Item::from(item),
+        );
+    }
+}
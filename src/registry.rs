@@ -0,0 +1,245 @@
+//! Pluggable contenders registration.
+//!
+//! Built-in demos are registered by [`Registry::builtin`]. Downstream crates can benchmark their own builder,
+//! by implementing [`Contender`] and registering it:
+//!
+//! ```rust
+//! # use rust_benchmark_setter::matrix::{Declaration,Feature,Support};
+//! # use rust_benchmark_setter::registry::{Contender,Hook,Registry};
+//! # use rust_benchmark_setter::report::{Format,Report};
+//! #[derive(Default)]
+//! struct Root {
+//!     number: u8,
+//! }
+//!
+//! impl Root {
+//!     fn number(mut self, number: u8) -> Self {
+//!         self.number = number;
+//!         self
+//!     }
+//! }
+//!
+//! struct InHouse;
+//!
+//! static DECLARATION: Declaration = Declaration {
+//!     name: "in-house",
+//!     version: "1.0.0",
+//!     module: "inhouse",
+//!     claims: &[
+//!         (Feature::ChainCall, Support::Supported),
+//!     ],
+//!     shapes: &[],
+//! };
+//!
+//! impl Contender for InHouse {
+//!     fn declaration(&self) -> &Declaration {
+//!         &DECLARATION
+//!     }
+//!
+//!     fn scenarios(&self) -> Vec<Hook> {
+//!         vec![
+//!             Hook { name: "usage", run: || assert_eq!(1, Root::default().number(1).number) },
+//!         ]
+//!     }
+//! }
+//!
+//! let mut registry = Registry::builtin();
+//! registry.register(InHouse);
+//!
+//! assert!(registry.run_scenarios().iter().all(|outcome| outcome.passed));
+//! let report = Report::new(&registry.declarations()).with_contenders(&["in-house"]);
+//! assert!(report.render(Format::Markdown).contains("| Chain call | 👍 |"));
+//! ```
+
+use std::panic;
+use crate::conformance::Adapter;
+use crate::matrix::Declaration;
+
+/// Named function, run as a scenario or a benchmark
+#[derive(Clone,Copy,Debug)]
+pub struct Hook {
+    pub name: &'static str,
+    pub run: fn(),
+}
+
+/// Named function, run with a count of collection items
+#[derive(Clone,Copy,Debug)]
+pub struct ScalingHook {
+    pub name: &'static str,
+    pub run: fn(usize),
+}
+
+/// A benchmarked builder/setter implementation
+pub trait Contender {
+    /// Name, version and feature claims
+    fn declaration(&self) -> &Declaration;
+
+    /// Self-checking scenarios, panicking on failure
+    fn scenarios(&self) -> Vec<Hook> {
+        vec![]
+    }
+
+    /// Benchmarked operations
+    fn benchmarks(&self) -> Vec<Hook> {
+        vec![]
+    }
+
+    /// Benchmarked operations, pushing given count of items through collection APIs
+    fn scaling(&self) -> Vec<ScalingHook> {
+        vec![]
+    }
+
+    /// Adapter running shared [conformance](crate::conformance) scenarios, scripts and fuzzing, if any
+    fn adapter(&self) -> Option<&dyn Adapter> {
+        None
+    }
+}
+
+/// Result of a scenario run
+#[derive(Clone,Debug,PartialEq)]
+pub struct Outcome {
+    pub contender: String,
+    pub scenario: &'static str,
+    pub passed: bool,
+}
+
+/// Registered contenders
+#[derive(Default)]
+pub struct Registry {
+    contenders: Vec<Box<dyn Contender>>,
+}
+
+impl Registry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry of built-in demos, among enabled features
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::registry::Registry;
+    /// let registry = Registry::builtin();
+    ///
+    /// assert!(registry.get("derive-builder").is_some());
+    /// assert!(registry.get("blueprint-setter").is_some());
+    /// ```
+    pub fn builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+        #[cfg(feature = "blueprint")]
+        registry
+            .register(crate::blueprint::setter::Demo)
+            .register(crate::blueprint::builder::Demo);
+        #[cfg(feature = "derive_builder")]
+        registry.register(crate::derivebuilder::Demo);
+        #[cfg(feature = "typed_builder")]
+        registry.register(crate::typedbuilder::Demo);
+        registry
+    }
+
+    pub fn register<C: Contender + 'static>(&mut self, contender: C) -> &mut Self {
+        self.contenders.push(Box::new(contender));
+        self
+    }
+
+    /// Keep only contenders matching `predicate`
+    pub fn retain<F: FnMut(&dyn Contender) -> bool>(&mut self, mut predicate: F) -> &mut Self {
+        self.contenders.retain(|contender| predicate(contender.as_ref()));
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Contender> {
+        self.contenders.iter().map(|contender| contender.as_ref())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Contender> {
+        self.iter().find(|contender| contender.declaration().name == name)
+    }
+
+    /// Declarations of registered contenders, to feed reports
+    pub fn declarations(&self) -> Vec<&Declaration> {
+        self.iter().map(|contender| contender.declaration()).collect()
+    }
+
+    /// Conformance adapters of registered contenders
+    pub fn adapters(&self) -> Vec<&dyn Adapter> {
+        self.iter().filter_map(|contender| contender.adapter()).collect()
+    }
+
+    /// Run scenarios of every contender, catching panic
+    pub fn run_scenarios(&self) -> Vec<Outcome> {
+        self.iter()
+            .flat_map(|contender| contender
+                .scenarios()
+                .into_iter()
+                .map(move |scenario| Outcome {
+                    contender: contender.declaration().name.to_owned(),
+                    scenario: scenario.name,
+                    passed: panic::catch_unwind(scenario.run).is_ok(),
+                })
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    pub fn builtin_scenarios_pass() {
+        let registry = Registry::builtin();
+        let outcomes = registry.run_scenarios();
+
+        for contender in registry.iter() {
+            let name = contender.declaration().name;
+            assert!(outcomes.iter().any(|outcome| outcome.contender == name), "{} has no scenario", name);
+        }
+        for outcome in outcomes {
+            assert!(outcome.passed, "{} failed {}", outcome.contender, outcome.scenario);
+        }
+    }
+
+    #[test]
+    pub fn builtin_benchmarks_run() {
+        for contender in Registry::builtin().iter() {
+            let benchmarks = contender.benchmarks();
+            assert!(!benchmarks.is_empty(), "{} has no benchmark", contender.declaration().name);
+            for benchmark in benchmarks {
+                (benchmark.run)();
+            }
+        }
+    }
+
+    #[test]
+    pub fn builtin_scaling_run() {
+        for contender in Registry::builtin().iter() {
+            let hooks = contender.scaling();
+            assert!(!hooks.is_empty(), "{} has no scaling benchmark", contender.declaration().name);
+            for hook in hooks {
+                (hook.run)(10);
+            }
+        }
+    }
+
+    #[test]
+    pub fn builtin_follows_features() {
+        let registry = Registry::builtin();
+
+        assert_eq!(cfg!(feature = "blueprint"), registry.get("blueprint-setter").is_some());
+        assert_eq!(cfg!(feature = "blueprint"), registry.get("blueprint-builder").is_some());
+        assert_eq!(cfg!(feature = "derive_builder"), registry.get("derive-builder").is_some());
+        assert_eq!(cfg!(feature = "typed_builder"), registry.get("typed-builder").is_some());
+    }
+
+    #[test]
+    pub fn names_are_unique() {
+        let registry = Registry::builtin();
+        let mut names = registry.declarations().iter().map(|declaration| declaration.name).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(registry.iter().count(), names.len());
+    }
+}
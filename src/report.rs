@@ -0,0 +1,510 @@
+//! Comparison report, as printed by the `report` binary.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+//! # use rust_benchmark_setter::report::{Format,Report};
+//! let report = Report::new(CONTENDERS)
+//!     .with_features(&[Feature::BuilderFunction, Feature::CollectionField])
+//!     .sorted_by_score();
+//!
+//! assert_eq!(
+//!     "contender,version,feature,support,weight,score\n\
+//!      derive-builder,0.12.0,builder-function,custom-code,1,1\n\
+//!      derive-builder,0.12.0,collection-field,field-configuration,1,3\n\
+//!      typed-builder,0.16.2,builder-function,supported,1,4\n\
+//!      typed-builder,0.16.2,collection-field,unsupported,1,0\n",
+//!     report.render(Format::Csv),
+//! );
+//! ```
+
+use std::fmt::Write;
+use std::str::FromStr;
+use crate::matrix::{Declaration,Feature,Shape};
+use crate::matrix::score::{Profile,Ranking};
+
+/// Output layout
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Format {
+    Markdown,
+    Json,
+    Csv,
+    Text,
+}
+
+/// Selection of features and contenders to compare
+#[derive(Clone,Debug)]
+pub struct Report<'a> {
+    features: Vec<Feature>,
+    contenders: Vec<&'a Declaration>,
+    profile: Profile,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [
+        Format::Markdown,
+        Format::Json,
+        Format::Csv,
+        Format::Text,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Format::Markdown => "markdown",
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Text => "text",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.key() == key)
+            .ok_or_else(|| format!("unknown format '{}'", key))
+    }
+}
+
+impl<'a> Report<'a> {
+    /// Report all features for `contenders`
+    pub fn new(contenders: &[&'a Declaration]) -> Self {
+        Self {
+            features: Feature::ALL.to_vec(),
+            contenders: contenders.to_vec(),
+            profile: Profile::uniform(),
+        }
+    }
+
+    /// Weight features with `profile`
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Keep only `features`, in matrix order. Empty selection keeps all features.
+    pub fn with_features(mut self, features: &[Feature]) -> Self {
+        if !features.is_empty() {
+            self.features.retain(|feature| features.contains(feature));
+        }
+        self
+    }
+
+    /// Keep only contenders whose name is in `names`. Empty selection keeps all contenders.
+    pub fn with_contenders<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        if !names.is_empty() {
+            self.contenders.retain(|contender| names.iter().any(|name| name.as_ref() == contender.name));
+        }
+        self
+    }
+
+    /// Sort contenders by descending weighted score over selected features
+    pub fn sorted_by_score(mut self) -> Self {
+        self.contenders = self.ranking().scores().iter().map(|score| score.contender).collect();
+        self
+    }
+
+    pub fn features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    pub fn contenders(&self) -> &[&'a Declaration] {
+        &self.contenders
+    }
+
+    /// Ranking of selected contenders over selected features
+    pub fn ranking(&self) -> Ranking<'a> {
+        Ranking::with_features(&self.contenders, &self.profile, &self.features)
+    }
+
+    /// Weighted score of `contender` over selected features
+    pub fn score(&self, contender: &Declaration) -> u32 {
+        self.features.iter().map(|feature| self.profile.score(contender, *feature)).sum()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Json => self.to_json(),
+            Format::Csv => self.to_csv(),
+            Format::Text => self.to_text(),
+        }
+    }
+
+    /// Drill-down of selected field-level features, per field shape
+    ///
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+    /// # use rust_benchmark_setter::report::{Format,Report};
+    /// let report = Report::new(CONTENDERS)
+    ///     .with_features(&[Feature::CollectionField, Feature::ChainCall])
+    ///     .with_contenders(&["derive-builder"]);
+    ///
+    /// assert_eq!(
+    ///     "contender,version,feature,shape,support\n\
+    ///      derive-builder,0.12.0,collection-field,vec,field-configuration\n\
+    ///      derive-builder,0.12.0,collection-field,hash-map,field-configuration\n",
+    ///     report.render_shapes(Format::Csv),
+    /// );
+    /// ```
+    pub fn render_shapes(&self, format: Format) -> String {
+        let features = self.features
+            .iter()
+            .copied()
+            .filter(|feature| !feature.shapes().is_empty())
+            .collect::<Vec<_>>();
+        let cell = |contender: &Declaration, feature: Feature, shape: Shape| contender.shape_support(feature, shape);
+        match format {
+            Format::Markdown => {
+                let mut markdown = String::new();
+                for contender in &self.contenders {
+                    writeln!(markdown, "### {} {}\n", contender.name, contender.version).unwrap();
+                    write!(markdown, "| Feature |").unwrap();
+                    for shape in Shape::ALL {
+                        write!(markdown, " {} |", shape.label()).unwrap();
+                    }
+                    writeln!(markdown).unwrap();
+                    writeln!(markdown, "| --- |{}", " --- |".repeat(Shape::ALL.len())).unwrap();
+                    for feature in &features {
+                        write!(markdown, "| {} |", feature.label()).unwrap();
+                        for shape in Shape::ALL {
+                            write!(markdown, " {} |", cell(contender, *feature, shape).map(|support| support.emoji()).unwrap_or("-")).unwrap();
+                        }
+                        writeln!(markdown).unwrap();
+                    }
+                    writeln!(markdown).unwrap();
+                }
+                markdown
+            },
+            Format::Json => {
+                let contenders = self.contenders
+                    .iter()
+                    .map(|contender| {
+                        let features = features
+                            .iter()
+                            .map(|feature| {
+                                let shapes = feature
+                                    .shapes()
+                                    .iter()
+                                    .filter_map(|shape| Some(format!("{}:{}", json_string(shape.key()), json_string(cell(contender, *feature, *shape)?.key()))))
+                                    .collect::<Vec<_>>()
+                                    .join(",");
+                                format!("{}:{{{}}}", json_string(feature.key()), shapes)
+                            })
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        format!(
+                            r#"{{"name":{},"version":{},"shapes":{{{}}}}}"#,
+                            json_string(contender.name),
+                            json_string(contender.version),
+                            features,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(r#"{{"contenders":[{}]}}"#, contenders)
+            },
+            Format::Csv => {
+                let mut csv = String::from("contender,version,feature,shape,support\n");
+                for contender in &self.contenders {
+                    for feature in &features {
+                        for shape in feature.shapes() {
+                            if let Some(support) = cell(contender, *feature, *shape) {
+                                writeln!(
+                                    csv,
+                                    "{},{},{},{},{}",
+                                    csv_field(contender.name), csv_field(contender.version), feature.key(), shape.key(), support.key(),
+                                ).unwrap();
+                            }
+                        }
+                    }
+                }
+                csv
+            },
+            Format::Text => {
+                let mut text = String::new();
+                for contender in &self.contenders {
+                    writeln!(text, "{} {}\n", contender.name, contender.version).unwrap();
+                    let mut rows = vec![
+                        Some("Feature").into_iter().chain(Shape::ALL.iter().map(|shape| shape.key())).map(str::to_owned).collect::<Vec<_>>(),
+                    ];
+                    for feature in &features {
+                        rows.push(
+                            Some(feature.label().to_owned())
+                                .into_iter()
+                                .chain(Shape::ALL.iter().map(|shape| cell(contender, *feature, *shape).map(|support| support.key()).unwrap_or("-").to_owned()))
+                                .collect()
+                        );
+                    }
+                    writeln!(text, "{}", text_table(&rows, &[1])).unwrap();
+                }
+                text
+            },
+        }
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+    /// # use rust_benchmark_setter::report::Report;
+    /// let report = Report::new(CONTENDERS).with_features(&[Feature::ChainCall]);
+    ///
+    /// assert_eq!(
+    ///     "| Feature | derive-builder 0.12.0 | typed-builder 0.16.2 |\n\
+    ///      | --- | --- | --- |\n\
+    ///      | Chain call | 👍 | 👍 |\n\
+    ///      | **Score** | 4 | 4 |\n",
+    ///     report.to_markdown(),
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        write!(markdown, "| Feature |").unwrap();
+        for contender in &self.contenders {
+            write!(markdown, " {} {} |", contender.name, contender.version).unwrap();
+        }
+        writeln!(markdown).unwrap();
+        write!(markdown, "| --- |").unwrap();
+        for _ in &self.contenders {
+            write!(markdown, " --- |").unwrap();
+        }
+        writeln!(markdown).unwrap();
+        for feature in &self.features {
+            write!(markdown, "| {} |", feature.label()).unwrap();
+            for contender in &self.contenders {
+                write!(markdown, " {} |", contender.support(*feature).map(|support| support.emoji()).unwrap_or("")).unwrap();
+            }
+            writeln!(markdown).unwrap();
+        }
+        write!(markdown, "| **Score** |").unwrap();
+        for contender in &self.contenders {
+            write!(markdown, " {} |", self.score(contender)).unwrap();
+        }
+        writeln!(markdown).unwrap();
+        let ranking = self.ranking();
+        let drivers = ranking.drivers();
+        if !drivers.is_empty() {
+            writeln!(markdown).unwrap();
+            writeln!(markdown, "Ranking drivers:").unwrap();
+            for driver in drivers {
+                writeln!(markdown, "* {}", driver).unwrap();
+            }
+        }
+        markdown
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+    /// # use rust_benchmark_setter::report::Report;
+    /// let report = Report::new(CONTENDERS)
+    ///     .with_features(&[Feature::ChainCall])
+    ///     .with_contenders(&["typed-builder"]);
+    ///
+    /// assert_eq!(
+    ///     r#"{"features":["chain-call"],"weights":{"chain-call":1},"contenders":[{"name":"typed-builder","version":"0.16.2","score":4,"support":{"chain-call":"supported"}}],"drivers":[]}"#,
+    ///     report.to_json(),
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let features = self.features
+            .iter()
+            .map(|feature| json_string(feature.key()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let contenders = self.contenders
+            .iter()
+            .map(|contender| {
+                let support = self.features
+                    .iter()
+                    .map(|feature| format!(
+                        "{}:{}",
+                        json_string(feature.key()),
+                        contender.support(*feature).map(|support| json_string(support.key())).unwrap_or_else(|| "null".to_owned()),
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    r#"{{"name":{},"version":{},"score":{},"support":{{{}}}}}"#,
+                    json_string(contender.name),
+                    json_string(contender.version),
+                    self.score(contender),
+                    support,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let weights = self.features
+            .iter()
+            .map(|feature| format!("{}:{}", json_string(feature.key()), self.profile.weight(*feature)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let ranking = self.ranking();
+        let drivers = ranking
+            .drivers()
+            .iter()
+            .map(|driver| format!(
+                r#"{{"feature":{},"weight":{},"spread":{}}}"#,
+                json_string(driver.feature.key()),
+                driver.weight,
+                driver.spread,
+            ))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(r#"{{"features":[{}],"weights":{{{}}},"contenders":[{}],"drivers":[{}]}}"#, features, weights, contenders, drivers)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("contender,version,feature,support,weight,score\n");
+        for contender in &self.contenders {
+            for feature in &self.features {
+                let support = contender.support(*feature);
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{}",
+                    csv_field(contender.name),
+                    csv_field(contender.version),
+                    feature.key(),
+                    support.map(|support| support.key()).unwrap_or(""),
+                    self.profile.weight(*feature),
+                    self.profile.score(contender, *feature),
+                ).unwrap();
+            }
+        }
+        csv
+    }
+
+    /// ```rust
+    /// # use rust_benchmark_setter::matrix::{CONTENDERS,Feature};
+    /// # use rust_benchmark_setter::report::Report;
+    /// let report = Report::new(CONTENDERS).with_features(&[Feature::BuilderFunction]);
+    ///
+    /// assert_eq!(
+    ///     "Feature         derive-builder 0.12.0  typed-builder 0.16.2\n\
+    ///      -----------------------------------------------------------\n\
+    ///      `fn builder()`  custom-code            supported\n\
+    ///      -----------------------------------------------------------\n\
+    ///      Score           1                      4\n\
+    ///      \n\
+    ///      Ranking drivers:\n  \
+    ///      `fn builder()` (weight 1): typed-builder 👍 4, derive-builder ✋ 1\n",
+    ///     report.to_text(),
+    /// );
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut rows = vec![];
+        rows.push(
+            Some("Feature".to_owned())
+                .into_iter()
+                .chain(self.contenders.iter().map(|contender| format!("{} {}", contender.name, contender.version)))
+                .collect::<Vec<_>>()
+        );
+        for feature in &self.features {
+            rows.push(
+                Some(feature.label().to_owned())
+                    .into_iter()
+                    .chain(self.contenders.iter().map(|contender| contender.support(*feature).map(|support| support.key()).unwrap_or("").to_owned()))
+                    .collect()
+            );
+        }
+        rows.push(
+            Some("Score".to_owned())
+                .into_iter()
+                .chain(self.contenders.iter().map(|contender| self.score(contender).to_string()))
+                .collect()
+        );
+        let mut text = text_table(&rows, &[1, rows.len() - 1]);
+        let ranking = self.ranking();
+        let drivers = ranking.drivers();
+        if !drivers.is_empty() {
+            writeln!(text).unwrap();
+            writeln!(text, "Ranking drivers:").unwrap();
+            for driver in drivers {
+                writeln!(text, "  {}", driver).unwrap();
+            }
+        }
+        text
+    }
+}
+
+/// Align `rows` into columns, inserting a separator line before each index of `separators`
+pub fn text_table(rows: &[Vec<String>], separators: &[usize]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths = (0..columns)
+        .map(|column| rows.iter().filter_map(|row| row.get(column)).map(|cell| cell.chars().count()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let total = widths.iter().sum::<usize>() + 2 * columns.saturating_sub(1);
+    let mut text = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        if separators.contains(&index) {
+            writeln!(text, "{}", "-".repeat(total)).unwrap();
+        }
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(text, "{}", line.trim_end()).unwrap();
+    }
+    text
+}
+
+/// Quote and escape `value` as a JSON string
+pub fn json_string(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Quote `value` as a CSV field, only when required
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "derive_builder", feature = "typed_builder"))]
+    #[test]
+    pub fn sort_by_score() {
+        let report = Report::new(crate::matrix::CONTENDERS)
+            .with_features(&[Feature::BuilderFunction])
+            .sorted_by_score();
+
+        let names = report.contenders().iter().map(|contender| contender.name).collect::<Vec<_>>();
+        assert_eq!(vec!["typed-builder", "derive-builder"], names);
+    }
+
+    #[cfg(feature = "derive_builder")]
+    #[test]
+    pub fn filter_contenders() {
+        let report = Report::new(crate::matrix::CONTENDERS).with_contenders(&["derive-builder"]);
+
+        assert_eq!(1, report.contenders().len());
+        assert_eq!(Feature::ALL.len(), report.features().len());
+    }
+
+    #[test]
+    pub fn escape() {
+        assert_eq!(r#""a\"b\\c\n""#, json_string("a\"b\\c\n"));
+        assert_eq!(r#""a,""b""#, csv_field("a,\"b"));
+        assert_eq!("ab", csv_field("ab"));
+    }
+}
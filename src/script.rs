@@ -0,0 +1,460 @@
+//! Text scenarios, replayed against every contender.
+//!
+//! A script is a list of statements, separated by `;` or new lines. `#` starts a comment:
+//!
+//! ```text
+//! # Build
+//! set number 1; set string "foo bar"
+//! push listitem {number 3}
+//! insert mapitem foobar {number 4}
+//! # Check
+//! expect number 1
+//! expect listitems 1; expect listitem 0 {number 3}
+//! expect mapitem foobar {number 4}
+//! ```
+//!
+//! Operations:
+//! * `set <field> <value>`, with `number`, `boolean`, `string`, `opt_string` or `opt_item` field
+//! * `push listitem <item>`
+//! * `insert mapitem <key> <item>`
+//!
+//! Expectations check the root built from preceding operations:
+//! * `expect <field> <value>`, with `number`, `boolean`, `string`, `opt_string` or `opt_item` field. `none` matches empty options.
+//! * `expect listitems <count>`, `expect listitem <index> <item>`
+//! * `expect mapitems <count>`, `expect mapitem <key> <item>`
+//!
+//! Items are written between braces, such as `{number 3 string foo}`. Operations only set item `number`.
+//! Values holding spaces, or looking like `none`, are double-quoted. Quoted values escape `\"`, `\\` and new lines `\n`.
+//!
+//! Scenario files are stored into `scenarios/<name>.scenario`, and run by tests and `report --scenario <path>`.
+//!
+//! ```rust
+//! # use rust_benchmark_setter::script::Script;
+//! # use rust_benchmark_setter::{blueprint,typedbuilder};
+//! let script = Script::parse("set number 1; set number 2\nexpect number 1").unwrap();
+//!
+//! assert_eq!(
+//!     "line 2: expected number 1, found 2",
+//!     script.run(&blueprint::setter::Demo).unwrap_err().to_string(),
+//! );
+//! // Setters can't be called twice
+//! assert_eq!(Ok(false), script.run(&typedbuilder::Demo));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::panic::{self,AssertUnwindSafe};
+use crate::conformance::{Adapter,Op};
+use crate::model::{Item,Root};
+
+/// Parsed scenario
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Script {
+    /// Statements, with their 1-based line number
+    pub statements: Vec<(usize, Statement)>,
+}
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum Statement {
+    Op(Op),
+    Expect(Expect),
+}
+
+/// Check on built root
+#[derive(Clone,Debug,PartialEq)]
+pub enum Expect {
+    Number(u8),
+    Boolean(bool),
+    String(String),
+    OptString(Option<String>),
+    OptItem(Option<Item>),
+    ListItems(usize),
+    ListItem(usize, Item),
+    MapItems(usize),
+    MapItem(String, Option<Item>),
+}
+
+/// Invalid statement, or failed expectation
+#[derive(Clone,Debug,PartialEq)]
+pub struct ScriptError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Clone,Debug,PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Open,
+    Close,
+    End,
+}
+
+/// Split `source` into tokens, with their line number
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ScriptError> {
+    let mut tokens = vec![];
+    for (index, line) in source.lines().enumerate() {
+        let error = |message: String| ScriptError { line: index + 1, message };
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '#' => break,
+                ';' => tokens.push((index + 1, Token::End)),
+                '{' => tokens.push((index + 1, Token::Open)),
+                '}' => tokens.push((index + 1, Token::Close)),
+                '"' => {
+                    let mut quoted = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(c @ ('"' | '\\')) => quoted.push(c),
+                                Some('n') => quoted.push('\n'),
+                                Some(c) => return Err(error(format!("invalid escape '\\{}'", c))),
+                                None => return Err(error("unterminated string".to_owned())),
+                            },
+                            Some(c) => quoted.push(c),
+                            None => return Err(error("unterminated string".to_owned())),
+                        }
+                    }
+                    tokens.push((index + 1, Token::Quoted(quoted)));
+                },
+                c if c.is_whitespace() => {},
+                c => {
+                    let mut word = String::from(c);
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, ';' | '{' | '}' | '"' | '#')) {
+                        word.push(c);
+                    }
+                    tokens.push((index + 1, Token::Word(word)));
+                },
+            }
+        }
+        tokens.push((index + 1, Token::End));
+    }
+    Ok(tokens)
+}
+
+/// Tokens of a statement
+struct Parser<'a> {
+    tokens: std::slice::Iter<'a, Token>,
+}
+
+impl<'a> Parser<'a> {
+    fn word(&mut self, expected: &str) -> Result<&'a str, String> {
+        match self.tokens.next() {
+            Some(Token::Word(word)) => Ok(word),
+            other => Err(format!("expected {}, found {}", expected, describe(other))),
+        }
+    }
+
+    fn text(&mut self, expected: &str) -> Result<String, String> {
+        match self.tokens.next() {
+            Some(Token::Word(text) | Token::Quoted(text)) => Ok(text.clone()),
+            other => Err(format!("expected {}, found {}", expected, describe(other))),
+        }
+    }
+
+    fn value<T: std::str::FromStr>(&mut self, expected: &str) -> Result<T, String> {
+        let word = self.word(expected)?;
+        word.parse().map_err(|_| format!("invalid {} '{}'", expected, word))
+    }
+
+    /// `none`, or `some` parsed value
+    fn optional<T, F: FnOnce(&mut Self) -> Result<T, String>>(&mut self, some: F) -> Result<Option<T>, String> {
+        if self.tokens.as_slice().first() == Some(&Token::Word("none".to_owned())) {
+            self.tokens.next();
+            return Ok(None);
+        }
+        some(self).map(Some)
+    }
+
+    fn item(&mut self) -> Result<Item, String> {
+        match self.tokens.next() {
+            Some(Token::Open) => {},
+            other => return Err(format!("expected item, found {}", describe(other))),
+        }
+        let mut item = Item::default();
+        loop {
+            match self.tokens.next() {
+                Some(Token::Close) => return Ok(item),
+                Some(Token::Word(field)) => match field.as_str() {
+                    "number" => item.number = self.value("number")?,
+                    "boolean" => item.boolean = self.value("boolean")?,
+                    "string" => item.string = self.text("string")?,
+                    "opt_string" => item.opt_string = self.optional(|parser| parser.text("opt_string"))?,
+                    other => return Err(format!("unknown item field '{}'", other)),
+                },
+                other => return Err(format!("expected item field or '}}', found {}", describe(other))),
+            }
+        }
+    }
+
+    /// Item, holding only `number` as operations can't set other fields
+    fn item_number(&mut self) -> Result<u8, String> {
+        let item = self.item()?;
+        if item != Item::number(item.number) {
+            return Err("only item number can be set".to_owned());
+        }
+        Ok(item.number)
+    }
+
+    fn op(&mut self, verb: &str) -> Result<Op, String> {
+        let op = match (verb, self.word("field")?) {
+            ("set", "number") => Op::Number(self.value("number")?),
+            ("set", "boolean") => Op::Boolean(self.value("boolean")?),
+            ("set", "string") => Op::String(self.text("string")?),
+            ("set", "opt_string") => Op::OptString(self.text("opt_string")?),
+            ("set", "opt_item") => Op::OptItem(self.item_number()?),
+            ("push", "listitem") => Op::ListItem(self.item_number()?),
+            ("insert", "mapitem") => Op::MapItem(self.text("key")?, self.item_number()?),
+            (verb, field) => return Err(format!("can't {} '{}'", verb, field)),
+        };
+        Ok(op)
+    }
+
+    fn expect(&mut self) -> Result<Expect, String> {
+        let expect = match self.word("field")? {
+            "number" => Expect::Number(self.value("number")?),
+            "boolean" => Expect::Boolean(self.value("boolean")?),
+            "string" => Expect::String(self.text("string")?),
+            "opt_string" => Expect::OptString(self.optional(|parser| parser.text("opt_string"))?),
+            "opt_item" => Expect::OptItem(self.optional(Self::item)?),
+            "listitems" => Expect::ListItems(self.value("count")?),
+            "listitem" => Expect::ListItem(self.value("index")?, self.item()?),
+            "mapitems" => Expect::MapItems(self.value("count")?),
+            "mapitem" => Expect::MapItem(self.text("key")?, self.optional(Self::item)?),
+            other => return Err(format!("can't expect '{}'", other)),
+        };
+        Ok(expect)
+    }
+
+    fn statement(&mut self) -> Result<Statement, String> {
+        let statement = match self.word("statement")? {
+            "expect" => Statement::Expect(self.expect()?),
+            verb @ ("set" | "push" | "insert") => Statement::Op(self.op(verb)?),
+            other => return Err(format!("unknown statement '{}'", other)),
+        };
+        match self.tokens.next() {
+            None => Ok(statement),
+            other => Err(format!("unexpected {}", describe(other))),
+        }
+    }
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        None | Some(Token::End) => "end of statement".to_owned(),
+        Some(Token::Word(word)) => format!("'{}'", word),
+        Some(Token::Quoted(text)) => format!("\"{}\"", text),
+        Some(Token::Open) => "'{'".to_owned(),
+        Some(Token::Close) => "'}'".to_owned(),
+    }
+}
+
+/// Write `text` as a single token, escaping quoted ones
+fn quote(text: &str) -> String {
+    let plain = !text.is_empty() && text != "none" && !text.contains(|c: char| c.is_whitespace() || matches!(c, ';' | '{' | '}' | '"' | '#' | '\\'));
+    if plain {
+        text.to_owned()
+    } else {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+    }
+}
+
+/// Write `ops` as a script
+///
+/// ```rust
+/// # use rust_benchmark_setter::conformance::Op;
+/// # use rust_benchmark_setter::script;
+/// assert_eq!(
+///     "set string \"\"; insert mapitem foo {number 1}",
+///     script::render(&[Op::String("".to_owned()), Op::MapItem("foo".to_owned(), 1)]),
+/// );
+/// ```
+pub fn render(ops: &[Op]) -> String {
+    ops
+        .iter()
+        .map(|op| match op {
+            Op::Number(number) => format!("set number {}", number),
+            Op::Boolean(boolean) => format!("set boolean {}", boolean),
+            Op::String(string) => format!("set string {}", quote(string)),
+            Op::OptString(string) => format!("set opt_string {}", quote(string)),
+            Op::OptItem(number) => format!("set opt_item {{number {}}}", number),
+            Op::ListItem(number) => format!("push listitem {{number {}}}", number),
+            Op::MapItem(key, number) => format!("insert mapitem {} {{number {}}}", quote(key), number),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl Expect {
+    /// Check `root`, describing mismatch
+    pub fn check(&self, root: &Root) -> Result<(), String> {
+        fn compare<T: PartialEq + fmt::Debug>(what: String, expected: &T, actual: &T) -> Result<(), String> {
+            if expected == actual {
+                Ok(())
+            } else {
+                Err(format!("expected {} {:?}, found {:?}", what, expected, actual))
+            }
+        }
+        match self {
+            Expect::Number(number) => compare("number".to_owned(), number, &root.number),
+            Expect::Boolean(boolean) => compare("boolean".to_owned(), boolean, &root.boolean),
+            Expect::String(string) => compare("string".to_owned(), string, &root.string),
+            Expect::OptString(string) => compare("opt_string".to_owned(), string, &root.opt_string),
+            Expect::OptItem(item) => compare("opt_item".to_owned(), item, &root.opt_item),
+            Expect::ListItems(count) => compare("listitems count".to_owned(), count, &root.listitems.len()),
+            Expect::ListItem(index, item) => compare(format!("listitem {}", index), &Some(item), &root.listitems.get(*index)),
+            Expect::MapItems(count) => compare("mapitems count".to_owned(), count, &root.mapitems.len()),
+            Expect::MapItem(key, item) => compare(format!("mapitem {}", key), &item.as_ref(), &root.mapitems.get(key)),
+        }
+    }
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let tokens = tokenize(source)?;
+        let mut script = Self::default();
+        for statement in tokens.split(|(_, token)| *token == Token::End).filter(|statement| !statement.is_empty()) {
+            let line = statement[0].0;
+            let tokens = statement.iter().map(|(_, token)| token.clone()).collect::<Vec<_>>();
+            let parsed = Parser { tokens: tokens.iter() }
+                .statement()
+                .map_err(|message| ScriptError { line, message })?;
+            script.statements.push((line, parsed));
+        }
+        Ok(script)
+    }
+
+    /// Operations, ignoring expectations
+    pub fn ops(&self) -> Vec<Op> {
+        self.statements
+            .iter()
+            .filter_map(|(_, statement)| match statement {
+                Statement::Op(op) => Some(op.clone()),
+                Statement::Expect(_) => None,
+            })
+            .collect()
+    }
+
+    /// Replay against `adapter`, building a root for each expectation from preceding operations
+    ///
+    /// Returns whether replayed, `false` when `adapter` can't express operations.
+    pub fn run(&self, adapter: &dyn Adapter) -> Result<bool, ScriptError> {
+        let mut ops = vec![];
+        for (line, statement) in &self.statements {
+            match statement {
+                Statement::Op(op) => ops.push(op.clone()),
+                Statement::Expect(expect) => {
+                    let error = |message: String| ScriptError { line: *line, message };
+                    let root = match panic::catch_unwind(AssertUnwindSafe(|| adapter.apply(&ops))) {
+                        Ok(Ok(root)) => root,
+                        Ok(Err(_)) => return Ok(false),
+                        Err(_) => return Err(error("unable to build root".to_owned())),
+                    };
+                    expect.check(&root).map_err(error)?;
+                },
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ScriptError {}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::registry::Registry;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn scenarios_pass() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let mut paths = std::fs::read_dir(&dir)
+            .expect("Unable to list scenarios")
+            .map(|entry| entry.expect("Unable to read scenario").path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "scenario"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert!(!paths.is_empty());
+
+        let registry = Registry::builtin();
+        let mut failures = vec![];
+        for path in paths {
+            let source = std::fs::read_to_string(&path).expect("Unable to read scenario");
+            let script = Script::parse(&source).unwrap_or_else(|error| panic!("Invalid {}: {}", path.display(), error));
+            for adapter in registry.adapters() {
+                if let Err(error) = script.run(adapter) {
+                    failures.push(format!("{} fails {} {}", adapter.declaration().name, path.display(), error));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    pub fn render_and_parse() {
+        let ops = vec![
+            Op::Number(1),
+            Op::Boolean(true),
+            Op::String("foo bar".to_owned()),
+            Op::OptString("none".to_owned()),
+            Op::OptItem(2),
+            Op::ListItem(3),
+            Op::MapItem("".to_owned(), 4),
+        ];
+
+        assert_eq!(ops, Script::parse(&render(&ops)).unwrap().ops());
+    }
+
+    #[test]
+    pub fn render_and_parse_escapes() {
+        let ops = vec![
+            Op::String("say \"foo\"".to_owned()),
+            Op::OptString("C:\\foo\\".to_owned()),
+            Op::MapItem("foo\nbar".to_owned(), 1),
+        ];
+
+        let rendered = render(&ops);
+        assert_eq!(r#"set string "say \"foo\""; set opt_string "C:\\foo\\"; insert mapitem "foo\nbar" {number 1}"#, rendered);
+        assert_eq!(ops, Script::parse(&rendered).unwrap().ops());
+    }
+
+    #[test]
+    pub fn expectations() {
+        let script = Script::parse(r#"
+            set opt_string "none"; insert mapitem foo {number 4}
+            expect opt_string "none"
+            expect opt_item none
+            expect mapitem foo {number 4 string ""}
+            expect mapitem bar none
+            expect listitems 0
+        "#).unwrap();
+
+        for adapter in Registry::builtin().adapters() {
+            assert_eq!(Ok(adapter.declaration().name != "typed-builder"), script.run(adapter), "{}", adapter.declaration().name);
+        }
+    }
+
+    #[test]
+    pub fn invalid_script() {
+        let error = |source| Script::parse(source).unwrap_err().to_string();
+
+        assert_eq!("line 1: unknown statement 'add'", error("add number 1"));
+        assert_eq!("line 2: invalid number '256'", error("set number 1\nset number 256"));
+        assert_eq!("line 1: only item number can be set", error("push listitem {number 1 boolean true}"));
+        assert_eq!("line 1: expected item, found end of statement", error("push listitem; set number 1"));
+        assert_eq!("line 1: unexpected 'foo'", error("set number 1 foo"));
+        assert_eq!("line 1: unterminated string", error("set string \"foo"));
+        assert_eq!("line 1: unterminated string", error("set string \"foo\\\""));
+        assert_eq!("line 1: invalid escape '\\t'", error("set string \"foo\\t\""));
+    }
+}
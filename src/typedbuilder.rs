@@ -7,17 +7,11 @@
 //! Cons:
 //! * `default` setting must be set on every field (including `Option<T>`)
 //!
-//! | Feature | [`typed-builder`](self) |
-//! | --- | --- |
-//! | [`fn builder()`](#feature---builder-function) | 👍 |
-//! | [`Into` field](#feature---into-field) | 🤏 |
-//! | [`Option` field](#feature---option-field) | ☝ |
-//! | [`Default` struct](#feature---default-struct) | 👎 |
-//! | [Collection field](#feature---collection-field) | 👎 |
-//! | [`Builder` field](#feature---builder-field) | 👎 |
-//! | [`Into` builder](#feature---into-builder) | 👎 |
-//! | [Chain call](#feature---chain-call) | 👍 |
-//! | [Builder customization](#feature---builder-customization) | 👎 |
+#![doc = include_str!("matrix/typedbuilder.md")]
+//!
+//! Support per field shape:
+//!
+#![doc = include_str!("matrix/typedbuilder.shapes.md")]
 //!
 //! ## Example
 //!
@@ -55,20 +49,29 @@
 //!     opt_string: Some("bar".to_owned()),
 //!     opt_item: Some(Item {
 //!         number: 2,
+//!         boolean: false,
+//!         string: "".to_owned(),
+//!         opt_string: None,
 //!     }),
 //!     listitems: vec![
 //!         Item {
 //!             number: 3,
+//!             boolean: false,
+//!             string: "".to_owned(),
+//!             opt_string: None,
 //!         }
 //!     ],
 //!     mapitems: HashMap::from([
 //!         ("foobar".to_owned(), Item {
 //!             number: 4,
+//!             boolean: false,
+//!             string: "".to_owned(),
+//!             opt_string: None,
 //!         })
 //!     ]),
 //! };
 //!
-//! //assert_eq!(expected, actual);
+//! assert_eq!(expected, actual);
 //! ```
 //!
 //! ## Details
@@ -195,89 +198,301 @@
 //!
 //! ##### Feature - Builder customization
 //!
-//! 👎
+//! ✋
+//!
+//! Custom `impl` block must target a builder typestate, a tuple holding `()` for each unset field:
+//!
+//! ```
+//! # use typed_builder::TypedBuilder;
+//! # #[derive(Debug,PartialEq)]
+//! #[derive(TypedBuilder)]
+//! struct Root {
+//!   foo: u8,
+//!   bar: u8,
+//! }
+//! impl RootBuilder<((), ())> {
+//!   pub fn foobar(self, foobar: (u8, u8)) -> Root {
+//!     self
+//!       .foo(foobar.0)
+//!       .bar(foobar.1)
+//!       .build()
+//!   }
+//! }
+//! # let root =
+//! Root::builder().foobar((1, 2));
+//! # assert_eq!(
+//! #     Root {
+//! #       foo: 1,
+//! #       bar: 2,
+//! #     },
+//! #     root,
+//! # );
+//! ```
+//!
+//! Custom methods can't set fields conditionally, as each setter changes builder type.
+//!
+//! ##### Feature - Builder reuse
+//!
+//! 🤏
+//!
+//! `build()` consumes the builder:
+//!
+//! ```compile_fail
+//! # use typed_builder::TypedBuilder;
+//! #[derive(TypedBuilder)]
+//! struct Root {
+//!   foo: u8,
+//! }
+//! let builder = Root::builder().foo(1);
+//! let first = builder.build();
+//! let second = builder.build();
+//! ```
 //!
-//! Can't create custom `impl` block for generated builders
+//! But builders implement `Clone`, when every set field does:
+//!
+//! ```
+//! # use typed_builder::TypedBuilder;
+//! #[derive(Debug,PartialEq,TypedBuilder)]
+//! struct Root {
+//!   foo: u8,
+//! }
+//! let builder = Root::builder().foo(1);
+//! let first = builder.clone().build();
+//! let second = builder.build();
+//! assert_eq!(first, second);
+//! ```
 //!
 
 use std::collections::HashMap;
 use typed_builder::TypedBuilder;
+use crate::matrix::{Declaration,Feature,Shape,Support};
+use crate::conformance::{Adapter,Op,Unsupported};
+use crate::model;
+use crate::registry::{Contender,Hook,ScalingHook};
 
-/// Root data structure
-#[derive(Clone,Debug,PartialEq)]
-#[derive(TypedBuilder)]
-#[builder(field_defaults(default,setter(into)))]
-pub struct Root {
-    pub number: u8,
-    pub boolean: bool,
-    pub string: String,
-    #[builder(setter(strip_option))]
-    pub opt_string: Option<String>,
-    #[builder(setter(strip_option))]
-    pub opt_item: Option<Item>,
-    pub listitems: Vec<Item>,
-    pub mapitems: HashMap<String, Item>,
+/// Declared support levels
+pub const DECLARATION: Declaration = Declaration {
+    name: "typed-builder",
+    version: "0.16.2",
+    module: "typedbuilder",
+    claims: &[
+        (Feature::BuilderFunction, Support::Supported),
+        (Feature::IntoField, Support::Partial),
+        (Feature::OptionField, Support::FieldConfiguration),
+        (Feature::DefaultStruct, Support::Unsupported),
+        (Feature::CollectionField, Support::Unsupported),
+        (Feature::BuilderField, Support::Unsupported),
+        (Feature::IntoBuilder, Support::Unsupported),
+        (Feature::ChainCall, Support::Supported),
+        (Feature::BuilderCustomization, Support::CustomCode),
+        (Feature::BuilderReuse, Support::Partial),
+    ],
+    shapes: &[
+        (Feature::IntoField, Shape::Scalar, Support::Supported),
+        (Feature::IntoField, Shape::String, Support::Supported),
+        (Feature::IntoField, Shape::Option, Support::Supported),
+        (Feature::IntoField, Shape::OptionStruct, Support::Supported),
+        (Feature::IntoField, Shape::Nested, Support::Supported),
+    ],
+};
+
+crate::benchmark_model! {
+    #[derive(Clone,Debug,PartialEq)]
+    #[derive(TypedBuilder)]
+    #[builder(field_defaults(default,setter(into)))]
+    map: HashMap,
+    option: [#[builder(setter(strip_option))]],
+    listitems: [],
+    mapitems: [],
 }
 
-/// Sub-item for Root data structure
-#[derive(Clone,Debug,PartialEq)]
-#[derive(TypedBuilder)]
-pub struct Item {
-    #[builder(default)]
-    pub number: u8,
+/// Demo usage, building every field
+pub fn usage() -> Root {
+    Root::builder()
+        .number(1)
+        .boolean(true)
+        .string("foo")
+        .opt_string("bar")
+        .opt_item(Item::builder().number(2).build())
+        .listitems([Item::builder().number(3).build()])
+        .mapitems([("foobar".to_owned(), Item::builder().number(4).build())])
+        .build()
+}
+
+/// Registered [`Contender`]
+pub struct Demo;
+
+impl Contender for Demo {
+    fn declaration(&self) -> &Declaration {
+        &DECLARATION
+    }
+
+    fn scenarios(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || assert_eq!(
+                Root {
+                    number: 1,
+                    boolean: true,
+                    string: "foo".to_owned(),
+                    opt_string: Some("bar".to_owned()),
+                    opt_item: Some(Item { number: 2, boolean: false, string: "".to_owned(), opt_string: None }),
+                    listitems: vec![Item { number: 3, boolean: false, string: "".to_owned(), opt_string: None }],
+                    mapitems: HashMap::from([("foobar".to_owned(), Item { number: 4, boolean: false, string: "".to_owned(), opt_string: None })]),
+                },
+                usage(),
+            ) },
+            Hook { name: "default", run: || assert_eq!(
+                Root {
+                    number: 0,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                    opt_item: None,
+                    listitems: vec![],
+                    mapitems: HashMap::new(),
+                },
+                Root::builder().build(),
+            ) },
+        ]
+    }
+
+    fn benchmarks(&self) -> Vec<Hook> {
+        vec![
+            Hook { name: "usage", run: || { std::hint::black_box(usage()); } },
+        ]
+    }
+
+    fn scaling(&self) -> Vec<ScalingHook> {
+        vec![
+            ScalingHook { name: "listitems", run: |items| {
+                let listitems = (0..items).map(|number| Item::builder().number(number as u8).build()).collect::<Vec<_>>();
+                std::hint::black_box(Root::builder().listitems(listitems).build());
+            } },
+            ScalingHook { name: "mapitems", run: |items| {
+                let mapitems = (0..items).map(|number| (number.to_string(), Item::builder().number(number as u8).build())).collect::<HashMap<_, _>>();
+                std::hint::black_box(Root::builder().mapitems(mapitems).build());
+            } },
+        ]
+    }
+
+    fn adapter(&self) -> Option<&dyn Adapter> {
+        Some(self)
+    }
+}
+
+/// Each field setter is called at most once, and collections are set as a whole: repeated fields and item operations are unsupported.
+///
+/// Setters can't be called conditionally either, as each one changes builder type: set fields are chained in declaration order,
+/// which builds the same root as operations order since none is repeated.
+impl Adapter for Demo {
+    fn apply(&self, ops: &[Op]) -> Result<model::Root, Unsupported> {
+        fn once<T>(field: &mut Option<T>, value: T) -> Result<(), Unsupported> {
+            match field.replace(value) {
+                None => Ok(()),
+                Some(_) => Err(Unsupported("setter can't be called twice")),
+            }
+        }
+
+        let (mut number, mut boolean, mut string, mut opt_string, mut opt_item) = (None, None, None, None, None);
+        for op in ops {
+            match op {
+                Op::Number(value) => once(&mut number, *value)?,
+                Op::Boolean(value) => once(&mut boolean, *value)?,
+                Op::String(value) => once(&mut string, value.as_str())?,
+                Op::OptString(value) => once(&mut opt_string, value.as_str())?,
+                Op::OptItem(value) => once(&mut opt_item, Item::builder().number(*value).build())?,
+                Op::ListItem(_) | Op::MapItem(_, _) => return Err(Unsupported("collection items can't be added one by one")),
+            }
+        }
+        macro_rules! chain {
+            ($builder:expr;) => { $builder.build() };
+            ($builder:expr; $field:ident $(, $fields:ident)*) => {
+                match $field {
+                    Some(value) => chain!($builder.$field(value); $($fields),*),
+                    None => chain!($builder; $($fields),*),
+                }
+            };
+        }
+        Ok(chain!(Root::builder(); number, boolean, string, opt_string, opt_item).into())
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::evidence;
+    use crate::matrix::Feature;
 
-    #[test]
-    pub fn usage() {
-        let actual = Root::builder()
-            .number(1)
-            .boolean(true)
-            .string("foo")
-            .opt_string("bar")
-            .opt_item(
-                Item::builder()
-                    .number(2)
-                    .build()
-            )
-            .listitems([
-                Item::builder()
-                    .number(3)
-                    .build(),
-            ])
-            .mapitems([
-                (
-                    "foobar".to_owned(),
+    evidence! {
+        #[evidence(Feature::BuilderFunction, Feature::IntoField, Feature::OptionField, Feature::ChainCall)]
+        pub fn usage() {
+            let actual = Root::builder()
+                .number(1)
+                .boolean(true)
+                .string("foo")
+                .opt_string("bar")
+                .opt_item(
                     Item::builder()
-                        .number(4)
+                        .number(2)
+                        .build()
+                )
+                .listitems([
+                    Item::builder()
+                        .number(3)
                         .build(),
-                ),
-            ])
-            .build();
-        let expected = Root {
-            number: 1,
-            boolean: true,
-            string: "foo".to_owned(),
-            opt_string: Some("bar".to_owned()),
-            opt_item: Some(Item {
-                number: 2,
-            }),
-            listitems: vec![
-                Item {
-                    number: 3,
-                }
-            ],
-            mapitems: HashMap::from([
-                ("foobar".to_owned(), Item {
-                    number: 4,
-                })
-            ]),
-        };
+                ])
+                .mapitems([
+                    (
+                        "foobar".to_owned(),
+                        Item::builder()
+                            .number(4)
+                            .build(),
+                    ),
+                ])
+                .build();
+            let expected = Root {
+                number: 1,
+                boolean: true,
+                string: "foo".to_owned(),
+                opt_string: Some("bar".to_owned()),
+                opt_item: Some(Item {
+                    number: 2,
+                    boolean: false,
+                    string: "".to_owned(),
+                    opt_string: None,
+                }),
+                listitems: vec![
+                    Item {
+                        number: 3,
+                        boolean: false,
+                        string: "".to_owned(),
+                        opt_string: None,
+                    }
+                ],
+                mapitems: HashMap::from([
+                    ("foobar".to_owned(), Item {
+                        number: 4,
+                        boolean: false,
+                        string: "".to_owned(),
+                        opt_string: None,
+                    })
+                ]),
+            };
 
-        assert_eq!(expected, actual);
+            assert_eq!(expected, actual);
+        }
+
+        #[evidence(Feature::BuilderReuse)]
+        pub fn cloned_build() {
+            let builder = Root::builder()
+                .number(1)
+                .listitems([Item::builder().number(2).build()]);
+
+            let first = builder.clone().build();
+            let second = builder.build();
+            assert_eq!(first, second);
+            assert_eq!(1, second.listitems.len());
+        }
     }
 
     #[test]
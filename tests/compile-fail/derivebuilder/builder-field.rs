@@ -0,0 +1,21 @@
+//! No support for nested data builders
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Root {
+    #[builder(setter(each(name="listitem",into)))]
+    pub listitems: Vec<Item>,
+}
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Item {
+    pub number: u8,
+}
+
+pub fn usage() -> Result<Root, RootBuilderError> {
+    RootBuilder::default()
+        .listitem_with(|item| item.number(1))
+        .build()
+}
@@ -0,0 +1,12 @@
+//! No static builder init from target struct
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default)]
+pub struct Root {
+    pub number: u8,
+}
+
+pub fn usage() -> RootBuilder {
+    Root::builder()
+}
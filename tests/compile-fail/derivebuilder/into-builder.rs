@@ -0,0 +1,20 @@
+//! No implemention of `Into` for builders
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into,strip_option))]
+pub struct Root {
+    pub opt_item: Option<Item>,
+}
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Item {
+    pub number: u8,
+}
+
+pub fn usage() -> Result<Root, RootBuilderError> {
+    RootBuilder::default()
+        .opt_item(ItemBuilder::default().number(1))
+        .build()
+}
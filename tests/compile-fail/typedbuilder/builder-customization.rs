@@ -0,0 +1,18 @@
+//! Custom methods can't set fields conditionally: each setter changes builder type
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct Root {
+    pub foo: u8,
+    pub bar: u8,
+}
+
+impl RootBuilder<((), ())> {
+    pub fn maybe_foo(self, foo: Option<u8>) -> RootBuilder<((u8,), ())> {
+        match foo {
+            Some(foo) => self.foo(foo),
+            None => self,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! No option to consume a builder to set a field
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct Root {
+    #[builder(setter(strip_option))]
+    pub opt_item: Option<Item>,
+}
+
+#[derive(TypedBuilder)]
+pub struct Item {
+    #[builder(default)]
+    pub number: u8,
+}
+
+pub fn usage() -> Root {
+    Root::builder()
+        .opt_item_with(|item| item.number(1))
+        .build()
+}
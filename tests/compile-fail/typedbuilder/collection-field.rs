@@ -0,0 +1,20 @@
+//! No option to enable adding a item to collection-like field
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct Root {
+    pub listitems: Vec<Item>,
+}
+
+#[derive(TypedBuilder)]
+pub struct Item {
+    #[builder(default)]
+    pub number: u8,
+}
+
+pub fn usage() -> Root {
+    Root::builder()
+        .listitem(Item::builder().number(1).build())
+        .build()
+}
@@ -0,0 +1,11 @@
+//! `Default` struct implementation is not supported
+use typed_builder::TypedBuilder;
+
+#[derive(Default,TypedBuilder)]
+pub struct Root {
+    pub number: u8,
+}
+
+pub fn usage() -> Root {
+    Root::builder().build()
+}
@@ -0,0 +1,29 @@
+//! Nested data builders can be added to builder
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Root {
+    #[builder(setter(each(name="listitem",into)))]
+    pub listitems: Vec<Item>,
+}
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Item {
+    pub number: u8,
+}
+
+impl RootBuilder {
+    pub fn listitem_with<F: FnOnce(&mut ItemBuilder) -> &mut ItemBuilder>(&mut self, f: F) -> Result<&mut Self, ItemBuilderError> {
+        let mut builder = ItemBuilder::default();
+        f(&mut builder);
+        Ok(self.listitem(builder.build()?))
+    }
+}
+
+pub fn usage() -> Result<Root, Box<dyn std::error::Error>> {
+    Ok(RootBuilder::default()
+        .listitem_with(|item| item.number(1))?
+        .build()?)
+}
@@ -0,0 +1,20 @@
+//! Static builder init can be added to target struct
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default)]
+pub struct Root {
+    pub number: u8,
+}
+
+impl Root {
+    pub fn builder() -> RootBuilder {
+        RootBuilder::default()
+    }
+}
+
+pub fn usage() -> Result<Root, RootBuilderError> {
+    Root::builder()
+        .number(1)
+        .build()
+}
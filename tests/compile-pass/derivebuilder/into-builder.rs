@@ -0,0 +1,26 @@
+//! `Into` implementation can be added for builders
+use derive_builder::Builder;
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into,strip_option))]
+pub struct Root {
+    pub opt_item: Option<Item>,
+}
+
+#[derive(Builder,Clone,Default)]
+#[builder(default,setter(into))]
+pub struct Item {
+    pub number: u8,
+}
+
+impl From<&mut ItemBuilder> for Item {
+    fn from(builder: &mut ItemBuilder) -> Self {
+        builder.build().expect("unable to build Item")
+    }
+}
+
+pub fn usage() -> Result<Root, RootBuilderError> {
+    RootBuilder::default()
+        .opt_item(ItemBuilder::default().number(1))
+        .build()
+}
@@ -0,0 +1,19 @@
+//! Custom `impl` block can be added for a concrete builder typestate, here with no field set
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct Root {
+    pub foo: u8,
+    pub bar: u8,
+}
+
+impl RootBuilder<((), ())> {
+    pub fn pair(self, pair: (u8, u8)) -> Root {
+        self.foo(pair.0).bar(pair.1).build()
+    }
+}
+
+pub fn usage() -> Root {
+    Root::builder().pair((1, 2))
+}
@@ -0,0 +1,157 @@
+//! Compile-fail verification of "not supported" claims (✋ and 👎).
+//!
+//! Each such claim is proven by a snippet `tests/compile-fail/<module>/<feature>.rs`, which must fail to compile.
+//! Errors are snapshotted next to it, into a `.stderr` file. Run `BLESS=1 cargo test --test compile_fail` to update them.
+//!
+//! Snapshots only hold error codes and primary messages, so that they survive `rustc` upgrades,
+//! and only change when a contender crate upgrade does.
+//!
+//! "Custom code possible" claims (✋) are also proven by a snippet `tests/compile-pass/<module>/<feature>.rs`, adding the custom code,
+//! which must compile.
+//!
+//! Snippets are type-checked with `rustc`, against contender crates already built into `target`.
+
+use std::fs;
+use std::path::{Path,PathBuf};
+use std::process::Command;
+use rust_benchmark_setter::matrix::{CONTENDERS,Feature,Support};
+
+/// Contender crates of enabled features
+const CRATES: &[&str] = &[
+    #[cfg(feature = "derive_builder")]
+    "derive_builder",
+    #[cfg(feature = "typed_builder")]
+    "typed_builder",
+];
+
+fn snippets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/compile-fail")
+}
+
+fn custom_code_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/compile-pass")
+}
+
+/// Most recent `lib<name>-<hash>.rlib` built into `deps`
+fn rlib(deps: &Path, name: &str) -> PathBuf {
+    let prefix = format!("lib{}-", name);
+    fs::read_dir(deps)
+        .expect("Unable to list dependencies")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            file_name.starts_with(&prefix) && file_name.ends_with(".rlib")
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .unwrap_or_else(|| panic!("Unable to find {} into {}", name, deps.display()))
+}
+
+/// Type-check `snippet`, returning error lines on failure
+fn compile(snippet: &Path) -> Result<(), String> {
+    let exe = std::env::current_exe().expect("Unable to locate test binary");
+    let deps = exe.parent().expect("Unable to locate dependencies");
+    let mut command = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned()));
+    command
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata"])
+        .arg("--out-dir").arg(env!("CARGO_TARGET_TMPDIR"))
+        .arg("-L").arg(format!("dependency={}", deps.display()));
+    for name in CRATES {
+        command.arg("--extern").arg(format!("{}={}", name, rlib(deps, name).display()));
+    }
+    let output = command.arg(snippet).output().expect("Unable to run rustc");
+    if output.status.success() {
+        return Ok(());
+    }
+    let errors = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter(|line| line.starts_with("error") && !line.starts_with("error: aborting"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    Err(errors)
+}
+
+/// Compare `errors` with `snapshot`, or overwrite it when `BLESS` is set
+fn check_snapshot(snapshot: &Path, errors: &str) -> Result<(), String> {
+    if std::env::var_os("BLESS").is_some() {
+        fs::write(snapshot, errors).expect("Unable to write snapshot");
+        return Ok(());
+    }
+    let expected = fs::read_to_string(snapshot).unwrap_or_default();
+    if expected == errors {
+        Ok(())
+    } else {
+        Err(format!("{} doesn't match diagnostic:\n--- expected\n{}--- actual\n{}", snapshot.display(), expected, errors))
+    }
+}
+
+#[test]
+pub fn unsupported_claims_fail_to_compile() {
+    let mut failures = vec![];
+    for declaration in CONTENDERS {
+        for (feature, support) in declaration.claims {
+            let snippet = snippets_dir().join(declaration.module).join(format!("{}.rs", feature.key()));
+            if support.is_supported() {
+                if snippet.exists() {
+                    failures.push(format!("{} claims {} {:?}, remove {}", declaration.name, support, feature, snippet.display()));
+                }
+                continue;
+            }
+            if !snippet.exists() {
+                failures.push(format!("{} claims {} {:?} without proof, add {}", declaration.name, support, feature, snippet.display()));
+                continue;
+            }
+            match compile(&snippet) {
+                Ok(()) => failures.push(format!(
+                    "{} now compiles: {} {} may support {:?}, update its claim",
+                    snippet.display(), declaration.name, declaration.version, feature,
+                )),
+                Err(errors) => if let Err(failure) = check_snapshot(&snippet.with_extension("stderr"), &errors) {
+                    failures.push(failure);
+                },
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+pub fn custom_code_compiles() {
+    let mut failures = vec![];
+    for declaration in CONTENDERS {
+        for (feature, support) in declaration.claims {
+            let snippet = custom_code_dir().join(declaration.module).join(format!("{}.rs", feature.key()));
+            if *support != Support::CustomCode {
+                if snippet.exists() {
+                    failures.push(format!("{} claims {} {:?}, remove {}", declaration.name, support, feature, snippet.display()));
+                }
+                continue;
+            }
+            if !snippet.exists() {
+                failures.push(format!("{} claims {} {:?} without custom code, add {}", declaration.name, support, feature, snippet.display()));
+                continue;
+            }
+            if let Err(errors) = compile(&snippet) {
+                failures.push(format!("{} doesn't compile:\n{}", snippet.display(), errors));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+pub fn snippets_match_a_feature() {
+    for declaration in CONTENDERS {
+        for dir in [snippets_dir(), custom_code_dir()] {
+            let dir = dir.join(declaration.module);
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries {
+                let path = entry.expect("Unable to read snippet").path();
+                let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+                assert!(stem.parse::<Feature>().is_ok(), "{} doesn't match any feature", path.display());
+            }
+        }
+    }
+}